@@ -1,8 +1,6 @@
-use futures_util::stream::StreamExt;
 use s3_simple::*;
 use std::os::unix::fs::MetadataExt;
 use tokio::fs::{self, File};
-use tokio::io::AsyncWriteExt;
 
 #[tokio::main]
 async fn main() -> Result<(), S3Error> {
@@ -57,16 +55,14 @@ async fn main() -> Result<(), S3Error> {
     // streaming download
     let path_output = "test_files/out.data";
     let mut file = fs::File::create(path_output).await?;
-    // The `S3Response` is simply a wrapper around `reqwest::Response`.
-    // You can decide, if you want to buffer the body in memory or
-    // convert it into a stream.
-    let res = bucket.get(&file_name).await?;
-    let stream = res.bytes_stream();
-    tokio::pin!(stream);
-    while let Some(Ok(item)) = stream.next().await {
-        let _ = file.write(item.as_ref()).await?;
-    }
-    // flush / sync all possibly left over data
+    // `.get_to_writer()` streams the body into any `AsyncWrite` and fails on
+    // connection errors in the middle of the stream.
+    // If you need more control, `.get()` returns the `S3Response`, which is
+    // simply a wrapper around `reqwest::Response`. You can decide, if you want
+    // to buffer the body in memory or convert it into a stream.
+    let written = bucket.get_to_writer(file_name, &mut file).await?;
+    assert_eq!(written, file_size as u64);
+    // sync all data to disk
     file.sync_all().await?;
 
     // make sure the files match
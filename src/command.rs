@@ -1,5 +1,7 @@
-use crate::constants::EMPTY_PAYLOAD_SHA;
+use crate::constants::{EMPTY_PAYLOAD_SHA, UNSIGNED_PAYLOAD};
+use crate::options::ChecksumAlgorithm;
 use crate::types::Multipart;
+use bytes::Bytes;
 use serde::Serialize;
 use sha2::{Digest, Sha256};
 use std::fmt;
@@ -10,15 +12,23 @@ pub struct Part {
     pub part_number: u32,
     #[serde(rename = "ETag")]
     pub etag: String,
+    /// The checksum sent with the part, which must be repeated on completion
+    #[serde(skip)]
+    pub checksum: Option<(ChecksumAlgorithm, String)>,
 }
 
 impl fmt::Display for Part {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>",
+            "<Part><PartNumber>{}</PartNumber><ETag>{}</ETag>",
             self.part_number, self.etag
-        )
+        )?;
+        if let Some((algorithm, checksum)) = &self.checksum {
+            let name = algorithm.xml_name();
+            write!(f, "<{}>{}</{}>", name, checksum, name)?;
+        }
+        write!(f, "</Part>")
     }
 }
 
@@ -39,44 +49,73 @@ impl fmt::Display for CompleteMultipartUploadData {
 
 impl CompleteMultipartUploadData {
     pub fn len(&self) -> usize {
-        self.to_string().as_bytes().len()
+        self.to_string().len()
     }
 
     #[allow(dead_code)]
     pub fn is_empty(&self) -> bool {
-        self.to_string().as_bytes().is_empty()
+        self.to_string().is_empty()
     }
 }
 
 #[allow(dead_code)]
 #[derive(Debug)]
 pub(crate) enum Command<'a> {
+    HeadBucket,
     HeadObject,
+    HeadObjectVersion {
+        version_id: &'a str,
+    },
     CopyObject {
         from: &'a str,
     },
     DeleteObject,
+    DeleteObjectVersion {
+        version_id: &'a str,
+    },
     DeleteObjectTagging,
     GetObject,
+    GetObjectVersion {
+        version_id: &'a str,
+    },
     GetObjectRange {
         start: u64,
         end: Option<u64>,
     },
     GetObjectTagging,
+    GetObjectLegalHold,
+    GetObjectRetention,
     PutObject {
-        content: &'a [u8],
+        content: Bytes,
         content_type: &'a str,
         multipart: Option<Multipart<'a>>,
     },
+    /// A single PUT with a body which is passed along separately and never buffered
+    PutObjectBody {
+        len: u64,
+        content_type: &'a str,
+    },
     PutObjectTagging {
         tags: &'a str,
     },
+    PutObjectLegalHold {
+        data: &'a str,
+    },
+    PutObjectRetention {
+        data: &'a str,
+    },
     ListMultipartUploads {
         prefix: Option<&'a str>,
         delimiter: Option<&'a str>,
         key_marker: Option<String>,
+        upload_id_marker: Option<String>,
         max_uploads: Option<usize>,
     },
+    ListObjectVersions {
+        prefix: &'a str,
+        key_marker: Option<String>,
+        version_id_marker: Option<String>,
+    },
     ListObjects {
         prefix: &'a str,
         delimiter: Option<&'a str>,
@@ -89,8 +128,19 @@ pub(crate) enum Command<'a> {
         continuation_token: Option<String>,
         start_after: Option<String>,
         max_keys: Option<usize>,
+        /// Provider specific query params, appended as-is
+        extra_query: &'a [(&'a str, &'a str)],
     },
     GetBucketLocation,
+    GetBucketCors,
+    GetBucketOwnershipControls,
+    GetPublicAccessBlock,
+    PutBucketCors {
+        data: &'a str,
+    },
+    DeleteObjects {
+        data: &'a str,
+    },
     // PresignGet {
     //     expiry_secs: u32,
     //     custom_queries: Option<HashMap<String, String>>,
@@ -111,48 +161,148 @@ pub(crate) enum Command<'a> {
     },
     UploadPart {
         part_number: u32,
-        content: &'a [u8],
+        content: Bytes,
         upload_id: &'a str,
     },
+    /// Copies the inclusive byte range `start..=end` of `from` into a part
+    UploadPartCopy {
+        from: &'a str,
+        multipart: Multipart<'a>,
+        start: u64,
+        end: u64,
+    },
     AbortMultipartUpload {
         upload_id: &'a str,
     },
     CompleteMultipartUpload {
         upload_id: &'a str,
-        data: CompleteMultipartUploadData,
+        data: &'a CompleteMultipartUploadData,
+    },
+    SelectObjectContent {
+        data: &'a str,
+    },
+    /// Any request without a body and without special handling
+    Raw {
+        method: http::Method,
     },
 }
 
 impl<'a> Command<'a> {
     pub(crate) fn http_method(&self) -> http::Method {
-        match *self {
+        match self {
             Command::GetObject
+            | Command::GetObjectVersion { .. }
             | Command::GetObjectRange { .. }
             | Command::ListObjects { .. }
             | Command::ListObjectsV2 { .. }
             | Command::GetBucketLocation
+            | Command::GetBucketCors
+            | Command::GetBucketOwnershipControls
+            | Command::GetPublicAccessBlock
             | Command::GetObjectTagging
+            | Command::GetObjectLegalHold
+            | Command::GetObjectRetention
+            | Command::ListObjectVersions { .. }
             | Command::ListMultipartUploads { .. } => http::Method::GET,
             Command::PutObject { .. }
+            | Command::PutObjectBody { .. }
             | Command::CopyObject { from: _ }
             | Command::PutObjectTagging { .. }
-            | Command::UploadPart { .. } => http::Method::PUT,
+            | Command::PutObjectLegalHold { .. }
+            | Command::PutObjectRetention { .. }
+            | Command::PutBucketCors { .. }
+            | Command::UploadPart { .. }
+            | Command::UploadPartCopy { .. } => http::Method::PUT,
             Command::DeleteObject
+            | Command::DeleteObjectVersion { .. }
             | Command::DeleteObjectTagging
             | Command::AbortMultipartUpload { .. } => http::Method::DELETE,
-            Command::InitiateMultipartUpload { .. } | Command::CompleteMultipartUpload { .. } => {
-                http::Method::POST
+            Command::InitiateMultipartUpload { .. }
+            | Command::CompleteMultipartUpload { .. }
+            | Command::DeleteObjects { .. }
+            | Command::SelectObjectContent { .. } => http::Method::POST,
+            Command::HeadBucket | Command::HeadObject | Command::HeadObjectVersion { .. } => {
+                http::Method::HEAD
             }
-            Command::HeadObject => http::Method::HEAD,
+            Command::Raw { method } => method.clone(),
+        }
+    }
+
+    /// The name of the S3 API operation, used for observability
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Command::HeadBucket => "HeadBucket",
+            Command::HeadObject | Command::HeadObjectVersion { .. } => "HeadObject",
+            Command::CopyObject { .. } => "CopyObject",
+            Command::DeleteObject | Command::DeleteObjectVersion { .. } => "DeleteObject",
+            Command::DeleteObjectTagging => "DeleteObjectTagging",
+            Command::GetObject
+            | Command::GetObjectVersion { .. }
+            | Command::GetObjectRange { .. } => "GetObject",
+            Command::GetObjectTagging => "GetObjectTagging",
+            Command::GetObjectLegalHold => "GetObjectLegalHold",
+            Command::GetObjectRetention => "GetObjectRetention",
+            Command::PutObject { .. } | Command::PutObjectBody { .. } => "PutObject",
+            Command::PutObjectTagging { .. } => "PutObjectTagging",
+            Command::PutObjectLegalHold { .. } => "PutObjectLegalHold",
+            Command::PutObjectRetention { .. } => "PutObjectRetention",
+            Command::ListMultipartUploads { .. } => "ListMultipartUploads",
+            Command::ListObjectVersions { .. } => "ListObjectVersions",
+            Command::ListObjects { .. } => "ListObjects",
+            Command::ListObjectsV2 { .. } => "ListObjectsV2",
+            Command::GetBucketLocation => "GetBucketLocation",
+            Command::GetBucketCors => "GetBucketCors",
+            Command::GetBucketOwnershipControls => "GetBucketOwnershipControls",
+            Command::GetPublicAccessBlock => "GetPublicAccessBlock",
+            Command::PutBucketCors { .. } => "PutBucketCors",
+            Command::DeleteObjects { .. } => "DeleteObjects",
+            Command::InitiateMultipartUpload { .. } => "CreateMultipartUpload",
+            Command::UploadPart { .. } => "UploadPart",
+            Command::UploadPartCopy { .. } => "UploadPartCopy",
+            Command::AbortMultipartUpload { .. } => "AbortMultipartUpload",
+            Command::CompleteMultipartUpload { .. } => "CompleteMultipartUpload",
+            Command::SelectObjectContent { .. } => "SelectObjectContent",
+            Command::Raw { .. } => "Raw",
+        }
+    }
+
+    /// Operations which can safely be sent again after a transient failure. Single parts of a
+    /// multipart upload can be retried as well, since each one is addressed independently.
+    pub(crate) fn is_retryable(&self) -> bool {
+        match self {
+            // the body has been consumed by the first attempt
+            Command::InitiateMultipartUpload { .. }
+            | Command::CompleteMultipartUpload { .. }
+            | Command::PutObjectBody { .. } => false,
+            Command::Raw { method } => method.is_idempotent(),
+            _ => true,
         }
     }
 
+    /// Operations which may return a `200` with an `<Error>` body on some providers
+    pub(crate) fn may_return_error_body(&self) -> bool {
+        matches!(
+            self,
+            Command::CopyObject { .. }
+                | Command::UploadPartCopy { .. }
+                | Command::CompleteMultipartUpload { .. }
+                | Command::DeleteObject
+                | Command::DeleteObjectVersion { .. }
+        )
+    }
+
     pub(crate) fn content_length(&self) -> usize {
         match &self {
             Command::PutObject { content, .. } => content.len(),
+            Command::PutObjectBody { len, .. } => *len as usize,
             Command::PutObjectTagging { tags } => tags.len(),
+            Command::PutObjectLegalHold { data }
+            | Command::PutObjectRetention { data }
+            | Command::PutBucketCors { data }
+            | Command::DeleteObjects { data } => data.len(),
             Command::UploadPart { content, .. } => content.len(),
             Command::CompleteMultipartUpload { data, .. } => data.len(),
+            Command::SelectObjectContent { data } => data.len(),
             _ => 0,
         }
     }
@@ -161,12 +311,23 @@ impl<'a> Command<'a> {
         match self {
             Command::InitiateMultipartUpload { content_type } => content_type,
             Command::PutObject { content_type, .. } => content_type,
-            Command::CompleteMultipartUpload { .. } => "application/xml",
+            Command::PutObjectBody { content_type, .. } => content_type,
+            Command::CompleteMultipartUpload { .. }
+            | Command::PutObjectLegalHold { .. }
+            | Command::PutObjectRetention { .. }
+            | Command::PutBucketCors { .. }
+            | Command::DeleteObjects { .. }
+            | Command::SelectObjectContent { .. } => "application/xml",
             _ => "text/plain",
         }
     }
 
-    pub(crate) fn sha256(&self) -> String {
+    pub(crate) fn sha256(&self, unsigned_payload: bool) -> String {
+        // the body cannot be hashed without consuming it
+        if unsigned_payload || matches!(self, Command::PutObjectBody { .. }) {
+            return UNSIGNED_PAYLOAD.to_string();
+        }
+
         match &self {
             Command::PutObject { content, .. } => {
                 let mut sha = Sha256::default();
@@ -183,6 +344,15 @@ impl<'a> Command<'a> {
                 sha.update(data.to_string().as_bytes());
                 hex::encode(sha.finalize().as_slice())
             }
+            Command::PutObjectLegalHold { data }
+            | Command::PutObjectRetention { data }
+            | Command::PutBucketCors { data }
+            | Command::DeleteObjects { data }
+            | Command::SelectObjectContent { data } => {
+                let mut sha = Sha256::default();
+                sha.update(data.as_bytes());
+                hex::encode(sha.finalize().as_slice())
+            }
             _ => EMPTY_PAYLOAD_SHA.into(),
         }
     }
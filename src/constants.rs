@@ -2,3 +2,4 @@ pub const LONG_DATE_TIME: &[time::format_description::BorrowedFormatItem<'static
     time::macros::format_description!("[year][month][day]T[hour][minute][second]Z");
 pub const EMPTY_PAYLOAD_SHA: &str =
     "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+pub const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
@@ -19,6 +19,11 @@ impl AccessKeyId {
     }
 }
 
+/// The secret part of the credentials.
+///
+/// With the `zeroize` feature enabled, the secret is wiped from memory when this value is
+/// dropped. Keep in mind that this only covers this value itself. Each clone wipes its own copy,
+/// but a `String` you moved out of it beforehand is not tracked anymore.
 #[derive(Clone)]
 pub struct AccessKeySecret(pub String);
 
@@ -40,10 +45,52 @@ impl AccessKeySecret {
     }
 }
 
-#[derive(Debug, Clone)]
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for AccessKeySecret {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for AccessKeySecret {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for AccessKeySecret {}
+
+/// With the `zeroize` feature enabled, the session token is wiped from memory on drop, just
+/// like the `AccessKeySecret`.
+#[derive(Clone)]
 pub struct Credentials {
     pub access_key_id: AccessKeyId,
     pub access_key_secret: AccessKeySecret,
+    /// The session token for temporary credentials, like the ones from STS `AssumeRole`.
+    /// If given, it will be sent and signed as `x-amz-security-token`.
+    pub session_token: Option<String>,
+}
+
+impl Debug for Credentials {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("access_key_id", &self.access_key_id)
+            .field("access_key_secret", &self.access_key_secret)
+            .field(
+                "session_token",
+                &self.session_token.as_ref().map(|_| "<hidden>"),
+            )
+            .finish()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Credentials {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.session_token);
+    }
 }
 
 impl Credentials {
@@ -54,16 +101,58 @@ impl Credentials {
         Self {
             access_key_id: AccessKeyId(key.into()),
             access_key_secret: AccessKeySecret(secret.into()),
+            session_token: None,
         }
     }
 
+    /// Temporary credentials with a session token, like the ones from STS `AssumeRole`
+    pub fn new_with_token<S>(key: S, secret: S, token: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            access_key_id: AccessKeyId(key.into()),
+            access_key_secret: AccessKeySecret(secret.into()),
+            session_token: Some(token.into()),
+        }
+    }
+
+    /// Reads `S3_ACCESS_KEY_ID`, `S3_ACCESS_KEY_SECRET` and the optional `S3_SESSION_TOKEN`
     pub fn try_from_env() -> Result<Self, S3Error> {
         let access_key_id = env::var("S3_ACCESS_KEY_ID")?;
         let access_key_secret = env::var("S3_ACCESS_KEY_SECRET")?;
+        let session_token = env::var("S3_SESSION_TOKEN").ok();
 
         Ok(Self {
             access_key_id: AccessKeyId(access_key_id),
             access_key_secret: AccessKeySecret(access_key_secret),
+            session_token,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_hides_secrets() {
+        let credentials = Credentials::new_with_token("key", "secret1", "token1");
+        let debug = format!("{:?}", credentials);
+        assert!(debug.contains("key"));
+        assert!(!debug.contains("secret1"));
+        assert!(!debug.contains("token1"));
+        assert!(debug.contains("session_token: Some(\"<hidden>\")"));
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize_secret() {
+        use zeroize::Zeroize;
+
+        let mut secret =
+            AccessKeySecret::new("wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY".to_string());
+        secret.zeroize();
+        assert!(secret.as_ref().is_empty());
+    }
+}
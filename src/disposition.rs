@@ -0,0 +1,129 @@
+/// Extracts the filename from a `Content-Disposition` header value like
+/// `attachment; filename="report.pdf"`.
+///
+/// The RFC 5987 extended form `filename*=UTF-8''%e2%82%ac%20rates.pdf` is decoded and
+/// preferred over a plain `filename`, if both are given. Returns `None` if there is no
+/// filename or it cannot be decoded.
+pub fn parse_disposition_filename(value: &str) -> Option<String> {
+    let mut filename = None;
+
+    for param in split_params(value).into_iter().skip(1) {
+        let Some((name, value)) = param.split_once('=') else {
+            continue;
+        };
+        let name = name.trim();
+        let value = value.trim();
+
+        if name.eq_ignore_ascii_case("filename*") {
+            if let Some(decoded) = decode_ext_value(value) {
+                return Some(decoded);
+            }
+        } else if name.eq_ignore_ascii_case("filename") && filename.is_none() {
+            filename = Some(unquote(value));
+        }
+    }
+
+    filename.filter(|f| !f.is_empty())
+}
+
+/// Splits a header value at each `;` outside of quoted strings.
+fn split_params(value: &str) -> Vec<&str> {
+    let mut params = Vec::new();
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (i, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => {
+                params.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    params.push(&value[start..]);
+
+    params
+}
+
+/// Removes surrounding quotes and resolves backslash escapes of a quoted string.
+fn unquote(value: &str) -> String {
+    let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) else {
+        return value.to_string();
+    };
+
+    let mut res = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(next) = chars.next() {
+                res.push(next);
+            }
+        } else {
+            res.push(c);
+        }
+    }
+    res
+}
+
+/// Decodes an RFC 5987 `charset'language'percent-encoded` value.
+fn decode_ext_value(value: &str) -> Option<String> {
+    let mut parts = value.splitn(3, '\'');
+    let charset = parts.next()?;
+    let _language = parts.next()?;
+    let encoded = parts.next()?;
+
+    let bytes = percent_encoding::percent_decode_str(encoded).collect::<Vec<u8>>();
+    if charset.eq_ignore_ascii_case("UTF-8") {
+        String::from_utf8(bytes).ok()
+    } else if charset.eq_ignore_ascii_case("ISO-8859-1") {
+        Some(bytes.into_iter().map(char::from).collect())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_disposition_filename() {
+        let filename = |value| parse_disposition_filename(value);
+
+        assert_eq!(
+            filename("attachment; filename=\"report.pdf\"").as_deref(),
+            Some("report.pdf")
+        );
+        assert_eq!(
+            filename("attachment; FILENAME=report.pdf").as_deref(),
+            Some("report.pdf")
+        );
+        assert_eq!(
+            filename(r#"attachment; filename="a \"quoted\"; name.txt""#).as_deref(),
+            Some("a \"quoted\"; name.txt")
+        );
+        assert_eq!(
+            filename("attachment; filename=\"rates.pdf\"; filename*=UTF-8''%e2%82%ac%20rates.pdf")
+                .as_deref(),
+            Some("€ rates.pdf")
+        );
+        assert_eq!(
+            filename("attachment; filename*=iso-8859-1'en'%A3%20rates.pdf").as_deref(),
+            Some("£ rates.pdf")
+        );
+
+        // an undecodable extended value falls back to the plain one
+        assert_eq!(
+            filename("attachment; filename*=UTF-8''%ff; filename=fallback.txt").as_deref(),
+            Some("fallback.txt")
+        );
+        assert_eq!(filename("inline"), None);
+        assert_eq!(filename("attachment; filename=\"\""), None);
+    }
+}
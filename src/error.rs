@@ -1,11 +1,24 @@
 use thiserror::Error;
 
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum S3Error {
+    #[error("access denied for bucket '{0}'")]
+    AccessDenied(String),
+    #[error("invalid content-md5: {0}")]
+    ContentMd5(&'static str),
+    #[error("invalid content type '{0}': must be visible ASCII and must not contain line breaks")]
+    ContentType(String),
     #[error("credentials: {0}")]
     Credentials(String),
+    #[error("an empty prefix would match every object in the bucket")]
+    EmptyPrefix,
+    #[error("the body exceeds the maximum size of {0} bytes")]
+    EntityTooLarge(u64),
     #[error("env var missing: {0}")]
     EnvVarMissing(#[from] std::env::VarError),
+    #[error("event stream: {0}")]
+    EventStream(String),
     #[error("fmt error: {0}")]
     FmtError(#[from] std::fmt::Error),
     #[error("from utf8: {0}")]
@@ -28,16 +41,35 @@ pub enum S3Error {
     InvalidHeaderName(#[from] http::header::InvalidHeaderName),
     #[error("invalid header value: {0}")]
     InvalidHeaderValue(#[from] http::header::InvalidHeaderValue),
+    #[error("invalid bucket options: {0}")]
+    InvalidOptions(String),
+    #[error("invalid S3 URI '{0}': expected 's3://bucket[/prefix]'")]
+    InvalidS3Uri(String),
     #[error("tokio task join: {0}")]
     Join(#[from] tokio::task::JoinError),
+    #[error("missing header in response: {0}")]
+    MissingHeader(&'static str),
+    #[error("bucket '{0}' does not exist")]
+    NoSuchBucket(String),
+    #[error("invalid multipart part size: {0}")]
+    PartSize(String),
+    #[error("precondition failed: {0}")]
+    PreconditionFailed(String),
     #[error("invalid range: {0}")]
     Range(&'static str),
     #[error("request: {0}")]
     Reqwest(#[from] reqwest::Error),
+    #[cfg(feature = "json")]
+    #[error("serde json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
     #[error("serde xml: {0}")]
     SerdeXml(#[from] quick_xml::de::DeError),
+    #[error("size mismatch: expected {expected} bytes, got {actual}")]
+    SizeMismatch { expected: u64, actual: u64 },
     #[error("Time format error: {0}")]
     TimeFormatError(#[from] time::error::Format),
+    #[error("Time parse error: {0}")]
+    TimeParse(#[from] time::error::Parse),
     #[error("url parse: {0}")]
     UrlParse(#[from] url::ParseError),
     #[error("Utf8 decoding error: {0}")]
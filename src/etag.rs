@@ -0,0 +1,69 @@
+/// Returns `true` if the ETag belongs to an object uploaded via multipart upload.
+///
+/// Such ETags have the form `"<hex>-<part count>"` and are not the MD5 digest of the content,
+/// which means they cannot be compared against a locally computed MD5.
+pub fn etag_is_multipart(etag: &str) -> bool {
+    etag_part_count(etag).is_some()
+}
+
+/// Returns the amount of parts of a multipart upload ETag, or `None` for a simple MD5 ETag.
+///
+/// Surrounding quotes and a weak `W/` prefix are ignored.
+pub fn etag_part_count(etag: &str) -> Option<u32> {
+    let etag = strip_quotes(etag);
+    let (digest, parts) = etag.split_once('-')?;
+    if digest.len() != 32 || !digest.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    if parts.is_empty() || !parts.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    parts.parse::<u32>().ok().filter(|count| *count > 0)
+}
+
+/// Removes surrounding quotes and a weak `W/` prefix from an ETag.
+pub(crate) fn strip_quotes(etag: &str) -> &str {
+    let etag = etag.trim();
+    let etag = etag.strip_prefix("W/").unwrap_or(etag);
+    etag.strip_prefix('"')
+        .and_then(|e| e.strip_suffix('"'))
+        .unwrap_or(etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_etag_part_count() {
+        assert_eq!(
+            etag_part_count("\"d41d8cd98f00b204e9800998ecf8427e-12\""),
+            Some(12)
+        );
+        assert_eq!(
+            etag_part_count("d41d8cd98f00b204e9800998ecf8427e-1"),
+            Some(1)
+        );
+        assert_eq!(
+            etag_part_count("W/\"d41d8cd98f00b204e9800998ecf8427e-3\""),
+            Some(3)
+        );
+
+        assert_eq!(
+            etag_part_count("\"d41d8cd98f00b204e9800998ecf8427e\""),
+            None
+        );
+        assert_eq!(etag_part_count("d41d8cd98f00b204e9800998ecf8427e-0"), None);
+        assert_eq!(etag_part_count("d41d8cd98f00b204e9800998ecf8427e-"), None);
+        assert_eq!(etag_part_count("d41d8cd98f00b204e9800998ecf8427e--1"), None);
+        assert_eq!(etag_part_count("not-a-digest-12"), None);
+        assert_eq!(etag_part_count(""), None);
+    }
+
+    #[test]
+    fn test_etag_is_multipart() {
+        assert!(etag_is_multipart("\"d41d8cd98f00b204e9800998ecf8427e-2\""));
+        assert!(!etag_is_multipart("\"d41d8cd98f00b204e9800998ecf8427e\""));
+    }
+}
@@ -8,15 +8,40 @@ use base64::Engine;
 use std::env;
 
 /// S3 Bucket operations, your main entrypoint
-pub use crate::bucket::{Bucket};
+pub use crate::bucket::Bucket;
 /// Custom options for bucket connections
-pub use crate::bucket::{BucketOptions};
+pub use crate::bucket::BucketOptions;
 /// S3 Credentials
 pub use crate::credentials::{AccessKeyId, AccessKeySecret, Credentials};
+/// `Content-Disposition` helpers
+pub use crate::disposition::parse_disposition_filename;
 /// Specialized S3 Error type which wraps errors from different sources
 pub use crate::error::S3Error;
+/// ETag helpers
+pub use crate::etag::{etag_is_multipart, etag_part_count};
+/// Manually driven multipart uploads
+pub use crate::multipart::MultipartUploadGuard;
+/// Request metrics via `metrics` crate facade
+#[cfg(feature = "metrics")]
+pub use crate::observer::MetricsObserver;
+/// Hook to observe requests
+pub use crate::observer::{Observer, RequestEvent};
+/// Custom options for single requests
+pub use crate::options::{
+    ChecksumAlgorithm, Encryption, IfCondition, ObjectOptions, PutOptions, SseCustomerKey,
+};
+/// Random access to objects via ranged GETs
+pub use crate::seekable::S3SeekableReader;
+/// S3 Select queries
+pub use crate::select::{SelectInput, SelectOutput, SelectQuery};
 /// Specialized Response objects
-pub use crate::types::{HeadObjectResult, Object, PutStreamResponse};
+pub use crate::types::{
+    CommonPrefix, CorsRule, DeleteError, DeleteResult, DeleteSummary, DeletedObject,
+    HeadObjectResult, ListBucketResult, MultipartUpload, Object, ObjectOwnership, ObjectRetention,
+    OpResponse, PresignedPost, PublicAccessBlock, PutResult, PutStreamResponse, RetentionMode,
+    StorageClass, VersionEntry,
+};
+pub use reqwest::Certificate as S3Certificate;
 pub use reqwest::Response as S3Response;
 pub use reqwest::StatusCode as S3StatusCode;
 
@@ -24,7 +49,16 @@ mod bucket;
 mod command;
 mod constants;
 mod credentials;
+pub mod debug;
+mod disposition;
 mod error;
+mod etag;
+mod multipart;
+mod observer;
+mod options;
+mod presign;
+mod seekable;
+mod select;
 mod signature;
 mod types;
 
@@ -37,7 +71,7 @@ impl Region {
     where
         S: Into<String>,
     {
-       Self(region.into())
+        Self(region.into())
     }
 
     pub fn try_from_env() -> Result<Self, S3Error> {
@@ -52,3 +86,67 @@ impl Region {
 fn md5_url_encode(s: &[u8]) -> String {
     general_purpose::STANDARD.encode(md5::compute(s).as_ref())
 }
+
+/// Escapes a value for the usage inside XML bodies we build by hand.
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Makes sure that a given `Content-MD5` is the base64 encoded 16 byte digest.
+fn validate_content_md5(md5: &str) -> Result<(), S3Error> {
+    match general_purpose::STANDARD.decode(md5) {
+        Ok(digest) if digest.len() == 16 => Ok(()),
+        Ok(_) => Err(S3Error::ContentMd5(
+            "the decoded digest must be exactly 16 bytes",
+        )),
+        Err(_) => Err(S3Error::ContentMd5("not valid base64")),
+    }
+}
+
+/// Validates a user given content type up front, so it does not fail later on with a generic
+/// `InvalidHeaderValue` during the header creation.
+fn validate_content_type(content_type: &str) -> Result<(), S3Error> {
+    if content_type.is_empty() || http::HeaderValue::from_str(content_type).is_err() {
+        Err(S3Error::ContentType(
+            content_type.escape_debug().to_string(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_content_md5() {
+        let md5 = md5_url_encode(b"Hello S3");
+        assert!(validate_content_md5(&md5).is_ok());
+
+        // valid base64 but only 3 bytes
+        assert!(validate_content_md5("AAAA").is_err());
+        assert!(validate_content_md5("no base64 at all").is_err());
+    }
+
+    #[test]
+    fn test_validate_content_type() {
+        assert!(validate_content_type("text/plain; charset=utf-8").is_ok());
+
+        assert!(validate_content_type("").is_err());
+        let err = validate_content_type("text/plain\r\nx-injected: 1").unwrap_err();
+        assert!(matches!(err, S3Error::ContentType(_)));
+        assert!(err.to_string().contains("text/plain\\r\\n"));
+    }
+}
@@ -0,0 +1,265 @@
+use crate::bucket::Bucket;
+use crate::command::Part;
+use crate::error::S3Error;
+use crate::types::PutStreamResponse;
+use bytes::Bytes;
+use std::mem;
+use tracing::{debug, error, warn};
+
+/// All parts except the last one must be at least 5 MiB
+const MIN_PART_SIZE: usize = 5 * 1024 * 1024;
+/// No part may be larger than 5 GiB
+const MAX_PART_SIZE: u64 = 5 * 1024 * 1024 * 1024;
+
+/// A manually driven multipart upload, created with `Bucket::start_multipart_upload()`.
+///
+/// Every upload must be finished with either `complete()` or `abort()`. Otherwise, the already
+/// uploaded parts stay on the server and keep accruing storage costs until they are cleaned
+/// up, for instance with `Bucket::abort_incomplete_uploads()`.
+///
+/// Since there is no async `Drop` in Rust, dropping an unfinished upload cannot clean it up
+/// properly. By default, only a warning is logged. With `abort_on_drop()`, a background task
+/// will be spawned on the current tokio runtime to abort it. There is no guarantee that this
+/// task will finish, for instance if the runtime is shutting down, so you should always
+/// finish uploads explicitly.
+#[derive(Debug)]
+pub struct MultipartUploadGuard {
+    bucket: Bucket,
+    key: String,
+    upload_id: String,
+    content_type: String,
+    parts: Vec<Part>,
+    uploaded_bytes: usize,
+    last_part_size: usize,
+    finished: bool,
+    abort_on_drop: bool,
+    strict_part_sizes: bool,
+}
+
+impl MultipartUploadGuard {
+    pub(crate) fn new(
+        bucket: Bucket,
+        key: String,
+        upload_id: String,
+        content_type: String,
+    ) -> Self {
+        Self {
+            bucket,
+            key,
+            upload_id,
+            content_type,
+            parts: Vec::new(),
+            uploaded_bytes: 0,
+            last_part_size: 0,
+            finished: false,
+            abort_on_drop: false,
+            strict_part_sizes: false,
+        }
+    }
+
+    /// Spawn a background task to abort the upload, if it is dropped before being finished.
+    pub fn abort_on_drop(mut self) -> Self {
+        self.abort_on_drop = true;
+        self
+    }
+
+    /// Return an error instead of only logging a warning, when a part smaller than 5 MiB is
+    /// followed by another one. S3 would reject such an upload on completion anyway.
+    pub fn strict_part_sizes(mut self) -> Self {
+        self.strict_part_sizes = true;
+        self
+    }
+
+    /// The object key this upload was initiated for
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The ID of this multipart upload
+    pub fn upload_id(&self) -> &str {
+        &self.upload_id
+    }
+
+    /// Uploads the next part and returns its part number.
+    ///
+    /// Each part except the last one must be at least 5 MiB in size, and no part may be larger
+    /// than 5 GiB. Since it is unknown if a part is the last one until the next one arrives,
+    /// a too small part is detected with the following `upload_part()`.
+    pub async fn upload_part(&mut self, content: &[u8]) -> Result<u32, S3Error> {
+        let part_number = self.parts.len() as u32 + 1;
+        self.check_part_size(part_number, content.len())?;
+
+        let part = self
+            .bucket
+            .multipart_request(
+                &self.key,
+                Bytes::copy_from_slice(content),
+                part_number,
+                &self.upload_id,
+                &self.content_type,
+            )
+            .await?;
+
+        self.parts.push(part);
+        self.uploaded_bytes += content.len();
+        self.last_part_size = content.len();
+        Ok(part_number)
+    }
+
+    fn check_part_size(&self, part_number: u32, size: usize) -> Result<(), S3Error> {
+        if size as u64 > MAX_PART_SIZE {
+            return Err(S3Error::PartSize(format!(
+                "part {} has {} bytes, while the maximum is 5 GiB",
+                part_number, size
+            )));
+        }
+
+        if part_number > 1 && self.last_part_size < MIN_PART_SIZE {
+            let msg = format!(
+                "part {} has only {} bytes, while all parts except the last one must have at \
+                least 5 MiB - the upload will be rejected on completion",
+                part_number - 1,
+                self.last_part_size
+            );
+            if self.strict_part_sizes {
+                return Err(S3Error::PartSize(msg));
+            }
+            warn!("{}", msg);
+        }
+
+        Ok(())
+    }
+
+    /// Completes the upload with all parts uploaded so far.
+    ///
+    /// If this fails, the upload is still unfinished and will be handled like any other
+    /// dropped upload.
+    pub async fn complete(mut self) -> Result<PutStreamResponse, S3Error> {
+        let parts = mem::take(&mut self.parts);
+        let res = self
+            .bucket
+            .complete_multipart_upload(&self.key, &self.upload_id, parts)
+            .await?;
+        self.finished = true;
+
+        PutStreamResponse::from_complete(res, self.uploaded_bytes).await
+    }
+
+    /// Aborts the upload and removes all parts uploaded so far.
+    pub async fn abort(mut self) -> Result<(), S3Error> {
+        self.bucket.abort_upload(&self.key, &self.upload_id).await?;
+        self.finished = true;
+        Ok(())
+    }
+}
+
+impl Drop for MultipartUploadGuard {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+
+        if !self.abort_on_drop {
+            warn!(
+                "multipart upload {} for {} has been dropped without being completed or \
+                aborted - it will stay on the server until it is cleaned up",
+                self.upload_id, self.key
+            );
+            return;
+        }
+
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            warn!(
+                "multipart upload {} for {} has been dropped outside of a tokio runtime \
+                and cannot be aborted",
+                self.upload_id, self.key
+            );
+            return;
+        };
+
+        debug!(
+            "aborting dropped multipart upload {} for {} in the background",
+            self.upload_id, self.key
+        );
+        let bucket = self.bucket.clone();
+        let key = mem::take(&mut self.key);
+        let upload_id = mem::take(&mut self.upload_id);
+        handle.spawn(async move {
+            if let Err(err) = bucket.abort_upload(&key, &upload_id).await {
+                error!(
+                    "error aborting dropped multipart upload {} for {}: {}",
+                    upload_id, key, err
+                );
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bucket::test_bucket;
+    use crate::BucketOptions;
+    use tracing_test::traced_test;
+
+    fn guard() -> MultipartUploadGuard {
+        let bucket = test_bucket("https://s3.example.com", BucketOptions::default()).unwrap();
+        MultipartUploadGuard::new(
+            bucket,
+            "test.txt".to_string(),
+            "upload1".to_string(),
+            "text/plain".to_string(),
+        )
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_drop_unfinished() {
+        drop(guard());
+        assert!(logs_contain(
+            "multipart upload upload1 for test.txt has been dropped without being completed"
+        ));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_drop_finished() {
+        let mut guard = guard();
+        guard.finished = true;
+        drop(guard);
+        assert!(!logs_contain("has been dropped"));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_check_part_size() {
+        let mut guard = guard();
+        guard.finished = true;
+
+        assert!(guard.check_part_size(1, 1).is_ok());
+        assert!(matches!(
+            guard.check_part_size(1, MAX_PART_SIZE as usize + 1),
+            Err(S3Error::PartSize(_))
+        ));
+
+        // a small part followed by another one
+        guard.last_part_size = MIN_PART_SIZE - 1;
+        assert!(guard.check_part_size(2, 1).is_ok());
+        assert!(logs_contain("part 1 has only 5242879 bytes"));
+
+        guard.strict_part_sizes = true;
+        assert!(matches!(
+            guard.check_part_size(2, 1),
+            Err(S3Error::PartSize(_))
+        ));
+        guard.last_part_size = MIN_PART_SIZE;
+        assert!(guard.check_part_size(2, 1).is_ok());
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_abort_on_drop_without_runtime() {
+        drop(guard().abort_on_drop());
+        assert!(logs_contain("has been dropped outside of a tokio runtime"));
+    }
+}
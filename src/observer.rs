@@ -0,0 +1,60 @@
+use std::fmt::Debug;
+use std::time::Duration;
+
+/// Hook to observe each request sent to S3, for instance to record metrics.
+///
+/// Set it via `BucketOptions::observer`. It is called synchronously after the response
+/// headers have been received, so it should be cheap and must not block.
+pub trait Observer: Debug + Send + Sync {
+    fn on_request(&self, event: &RequestEvent<'_>);
+}
+
+/// Information about a single finished request
+#[derive(Debug, Clone)]
+pub struct RequestEvent<'a> {
+    /// The S3 API operation like `GetObject` or `UploadPart`
+    pub op: &'static str,
+    pub method: &'a http::Method,
+    /// The HTTP status, `None` if the request failed without a response
+    pub status: Option<u16>,
+    /// The time until the response headers have been received. Streamed bodies are not
+    /// included.
+    pub duration: Duration,
+    /// The size of the request body
+    pub bytes_sent: u64,
+    /// The size of the response body, if the response contains a `Content-Length`
+    pub bytes_received: Option<u64>,
+}
+
+/// An `Observer` which records the following metrics via the `metrics` crate facade:
+///
+/// - `s3_request_duration_seconds{op}` histogram
+/// - `s3_request_bytes{op, direction}` histogram with `direction` being `sent` or `received`
+/// - `s3_request_total{op, status}` counter, with `status` being `error` for requests that
+///   failed without a response
+///
+/// With the `metrics` feature enabled, it is set in the `BucketOptions` by default. You only
+/// need to install a recorder, like `metrics-exporter-prometheus`.
+#[cfg(feature = "metrics")]
+#[derive(Debug, Default, Clone)]
+pub struct MetricsObserver;
+
+#[cfg(feature = "metrics")]
+impl Observer for MetricsObserver {
+    fn on_request(&self, event: &RequestEvent<'_>) {
+        let status = event
+            .status
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "error".to_string());
+
+        metrics::histogram!("s3_request_duration_seconds", "op" => event.op)
+            .record(event.duration.as_secs_f64());
+        metrics::histogram!("s3_request_bytes", "op" => event.op, "direction" => "sent")
+            .record(event.bytes_sent as f64);
+        if let Some(received) = event.bytes_received {
+            metrics::histogram!("s3_request_bytes", "op" => event.op, "direction" => "received")
+                .record(received as f64);
+        }
+        metrics::counter!("s3_request_total", "op" => event.op, "status" => status).increment(1);
+    }
+}
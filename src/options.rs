@@ -0,0 +1,470 @@
+use crate::error::S3Error;
+use crate::signature::uri_encode;
+use crate::{md5_url_encode, validate_content_type};
+use base64::engine::general_purpose;
+use base64::Engine;
+use http::header::{
+    CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_ENCODING, CONTENT_LANGUAGE, CONTENT_TYPE, IF_MATCH,
+    IF_NONE_MATCH,
+};
+use http::{HeaderMap, HeaderName, HeaderValue};
+use sha2::{Digest, Sha256};
+use std::fmt::{Debug, Formatter};
+
+/// Additional options for a single PUT request
+#[derive(Debug, Default, Clone)]
+pub struct PutOptions {
+    /// The content type of the object, defaults to `BucketOptions::default_content_type` or
+    /// `application/octet-stream`. A content type inside `object` takes precedence, just like
+    /// all other per-request headers.
+    pub content_type: Option<String>,
+    /// A precomputed, base64 encoded MD5 digest of the content. If given, it will be sent as
+    /// `Content-MD5` as-is instead of computing it, which saves a full pass over the data for
+    /// large in-memory uploads.
+    ///
+    /// It must be the digest of the whole object and is only used for a single PUT. Multipart
+    /// uploads never receive it, each `UploadPart` computes the digest of its own part.
+    pub content_md5: Option<String>,
+    /// Only write the object if the given condition holds. Otherwise, the request fails with
+    /// `S3Error::PreconditionFailed`.
+    pub if_condition: Option<IfCondition>,
+    /// Sends an additional checksum of the content, which S3 verifies before storing the
+    /// object. Overwrites `BucketOptions::checksum_algorithm` for this request.
+    pub checksum: Option<ChecksumAlgorithm>,
+    /// Additional object headers like cache control or metadata
+    pub object: ObjectOptions,
+}
+
+/// A condition for a PUT, which allows for optimistic concurrency on top of S3
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IfCondition {
+    /// Only overwrite the object if its current ETag matches, via `If-Match`
+    Match(String),
+    /// Only create the object if it does not exist yet, via `If-None-Match: *`
+    NoneMatch,
+}
+
+impl IfCondition {
+    pub(crate) fn header(&self) -> Result<(HeaderName, HeaderValue), S3Error> {
+        match self {
+            IfCondition::Match(etag) => Ok((IF_MATCH, HeaderValue::try_from(etag.as_str())?)),
+            IfCondition::NoneMatch => Ok((IF_NONE_MATCH, HeaderValue::from_static("*"))),
+        }
+    }
+}
+
+impl From<ObjectOptions> for PutOptions {
+    fn from(object: ObjectOptions) -> Self {
+        Self {
+            object,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<HeaderMap> for PutOptions {
+    fn from(headers: HeaderMap) -> Self {
+        Self::from(ObjectOptions::from(headers))
+    }
+}
+
+/// An additional checksum, which S3 verifies on upload and stores with the object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+    Sha256,
+    Crc32,
+    Crc32c,
+}
+
+impl ChecksumAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Sha256 => "SHA256",
+            ChecksumAlgorithm::Crc32 => "CRC32",
+            ChecksumAlgorithm::Crc32c => "CRC32C",
+        }
+    }
+
+    /// The request header for the checksum value, like `x-amz-checksum-sha256`
+    pub(crate) fn header_name(&self) -> HeaderName {
+        match self {
+            ChecksumAlgorithm::Sha256 => HeaderName::from_static("x-amz-checksum-sha256"),
+            ChecksumAlgorithm::Crc32 => HeaderName::from_static("x-amz-checksum-crc32"),
+            ChecksumAlgorithm::Crc32c => HeaderName::from_static("x-amz-checksum-crc32c"),
+        }
+    }
+
+    /// The element name inside the `CompleteMultipartUpload` body, like `ChecksumSHA256`
+    pub(crate) fn xml_name(&self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Sha256 => "ChecksumSHA256",
+            ChecksumAlgorithm::Crc32 => "ChecksumCRC32",
+            ChecksumAlgorithm::Crc32c => "ChecksumCRC32C",
+        }
+    }
+
+    /// The base64 encoded checksum of the content
+    pub(crate) fn checksum(&self, content: &[u8]) -> String {
+        match self {
+            ChecksumAlgorithm::Sha256 => {
+                general_purpose::STANDARD.encode(Sha256::digest(content).as_slice())
+            }
+            ChecksumAlgorithm::Crc32 => {
+                general_purpose::STANDARD.encode(crc32fast::hash(content).to_be_bytes())
+            }
+            ChecksumAlgorithm::Crc32c => {
+                general_purpose::STANDARD.encode(crc32c::crc32c(content).to_be_bytes())
+            }
+        }
+    }
+}
+
+/// Server side encryption of an object at rest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Encryption {
+    /// SSE-S3 with keys managed by S3, via `x-amz-server-side-encryption: AES256`
+    S3,
+    /// SSE-KMS via `x-amz-server-side-encryption: aws:kms`, with the default KMS key of the
+    /// account, if no `key_id` is given
+    Kms { key_id: Option<String> },
+}
+
+/// A customer provided 256 bit key for SSE-C, set via `BucketOptions::sse_customer_key`.
+///
+/// S3 does not store the key, so the same one must be sent with every request for the object,
+/// including all parts of a multipart upload. Losing it means losing the data.
+#[derive(Clone)]
+pub struct SseCustomerKey {
+    /// base64 encoded key
+    key: String,
+    /// base64 encoded MD5 digest of the raw key
+    key_md5: String,
+}
+
+impl Debug for SseCustomerKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SseCustomerKey(<hidden>, md5: {})", self.key_md5)
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for SseCustomerKey {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.key);
+    }
+}
+
+impl SseCustomerKey {
+    pub fn new(key: [u8; 32]) -> Self {
+        Self {
+            key: general_purpose::STANDARD.encode(key),
+            key_md5: md5_url_encode(&key),
+        }
+    }
+
+    /// Inserts the SSE-C headers for the object itself, or for the source of a copy.
+    pub(crate) fn apply(&self, headers: &mut HeaderMap, copy_source: bool) -> Result<(), S3Error> {
+        let prefix = if copy_source {
+            "x-amz-copy-source-server-side-encryption-customer"
+        } else {
+            "x-amz-server-side-encryption-customer"
+        };
+        headers.insert(
+            HeaderName::try_from(format!("{}-algorithm", prefix))?,
+            HeaderValue::from_static("AES256"),
+        );
+        let mut key = HeaderValue::from_str(&self.key)?;
+        key.set_sensitive(true);
+        headers.insert(HeaderName::try_from(format!("{}-key", prefix))?, key);
+        headers.insert(
+            HeaderName::try_from(format!("{}-key-md5", prefix))?,
+            HeaderValue::from_str(&self.key_md5)?,
+        );
+        Ok(())
+    }
+}
+
+/// Typed builder for the most common object headers, shared across PUT, copy and streaming
+/// uploads, so you don't need to know the exact S3 header names.
+///
+/// A raw `HeaderMap` can be converted into `ObjectOptions` as well. Typed values set via the
+/// builder win over the same header inside a raw map.
+#[derive(Debug, Default, Clone)]
+pub struct ObjectOptions {
+    headers: HeaderMap,
+    values: Vec<(HeaderName, String)>,
+    metadata: Vec<(String, String)>,
+    tags: Vec<(String, String)>,
+}
+
+impl From<HeaderMap> for ObjectOptions {
+    fn from(headers: HeaderMap) -> Self {
+        Self {
+            headers,
+            ..Default::default()
+        }
+    }
+}
+
+impl ObjectOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `Cache-Control`
+    pub fn cache_control<S: Into<String>>(self, value: S) -> Self {
+        self.value(CACHE_CONTROL, value)
+    }
+
+    /// `Content-Disposition`
+    pub fn content_disposition<S: Into<String>>(self, value: S) -> Self {
+        self.value(CONTENT_DISPOSITION, value)
+    }
+
+    /// `Content-Encoding`
+    pub fn content_encoding<S: Into<String>>(self, value: S) -> Self {
+        self.value(CONTENT_ENCODING, value)
+    }
+
+    /// `Content-Language`
+    pub fn content_language<S: Into<String>>(self, value: S) -> Self {
+        self.value(CONTENT_LANGUAGE, value)
+    }
+
+    /// `Content-Type`
+    pub fn content_type<S: Into<String>>(self, value: S) -> Self {
+        self.value(CONTENT_TYPE, value)
+    }
+
+    /// Adds user defined metadata, which will be sent as `x-amz-meta-{key}`
+    pub fn metadata<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.metadata.push((key.into(), value.into()));
+        self
+    }
+
+    /// Replace the metadata of the source object on copy with the metadata given here,
+    /// instead of keeping the original one, via `x-amz-metadata-directive: REPLACE`.
+    pub fn replace_metadata(self) -> Self {
+        self.value(
+            HeaderName::from_static("x-amz-metadata-directive"),
+            "REPLACE",
+        )
+    }
+
+    /// The storage class like `STANDARD` or `STANDARD_IA` via `x-amz-storage-class`
+    pub fn storage_class<S: Into<String>>(self, value: S) -> Self {
+        self.value(HeaderName::from_static("x-amz-storage-class"), value)
+    }
+
+    /// A canned ACL like `private` or `public-read` via `x-amz-acl`
+    pub fn acl<S: Into<String>>(self, value: S) -> Self {
+        self.value(HeaderName::from_static("x-amz-acl"), value)
+    }
+
+    /// The server side encryption algorithm like `AES256` or `aws:kms`
+    pub fn server_side_encryption<S: Into<String>>(self, value: S) -> Self {
+        self.value(
+            HeaderName::from_static("x-amz-server-side-encryption"),
+            value,
+        )
+    }
+
+    /// The KMS key ID, when `aws:kms` is used as server side encryption
+    pub fn sse_kms_key_id<S: Into<String>>(self, value: S) -> Self {
+        self.value(
+            HeaderName::from_static("x-amz-server-side-encryption-aws-kms-key-id"),
+            value,
+        )
+    }
+
+    /// Server side encryption with SSE-S3 or SSE-KMS.
+    ///
+    /// For multipart uploads, it is sent with the initiating request only.
+    pub fn encryption(self, encryption: Encryption) -> Self {
+        match encryption {
+            Encryption::S3 => self.server_side_encryption("AES256"),
+            Encryption::Kms { key_id } => {
+                let options = self.server_side_encryption("aws:kms");
+                match key_id {
+                    Some(key_id) => options.sse_kms_key_id(key_id),
+                    None => options,
+                }
+            }
+        }
+    }
+
+    /// Adds an object tag, which will be sent URL encoded via `x-amz-tagging`
+    pub fn tag<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.tags.push((key.into(), value.into()));
+        self
+    }
+
+    /// Any other header by name
+    pub fn header<S: Into<String>>(self, name: HeaderName, value: S) -> Self {
+        self.value(name, value)
+    }
+
+    fn value<S: Into<String>>(mut self, name: HeaderName, value: S) -> Self {
+        self.values.push((name, value.into()));
+        self
+    }
+
+    /// Converts these options into the final headers.
+    /// Fails, if any name or value is not valid inside an HTTP header.
+    pub fn into_headers(self) -> Result<HeaderMap, S3Error> {
+        let mut headers = self.headers;
+
+        for (name, value) in self.values {
+            if name == CONTENT_TYPE {
+                validate_content_type(&value)?;
+            }
+            headers.insert(name, HeaderValue::try_from(value)?);
+        }
+
+        for (key, value) in self.metadata {
+            let name = HeaderName::try_from(format!("x-amz-meta-{}", key))?;
+            headers.insert(name, HeaderValue::try_from(value)?);
+        }
+
+        if !self.tags.is_empty() {
+            let tagging = self
+                .tags
+                .iter()
+                .map(|(k, v)| format!("{}={}", uri_encode(k, true), uri_encode(v, true)))
+                .collect::<Vec<_>>()
+                .join("&");
+            headers.insert(
+                HeaderName::from_static("x-amz-tagging"),
+                HeaderValue::try_from(tagging)?,
+            );
+        }
+
+        Ok(headers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_object_options_into_headers() {
+        let mut raw = HeaderMap::new();
+        raw.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        raw.insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+
+        let headers = ObjectOptions::from(raw)
+            .content_type("application/json")
+            .metadata("Project", "s3-simple")
+            .storage_class("STANDARD_IA")
+            .tag("team", "a b")
+            .tag("env", "prod")
+            .into_headers()
+            .unwrap();
+
+        assert_eq!(headers.get(CACHE_CONTROL).unwrap(), "no-cache");
+        assert_eq!(headers.get(CONTENT_TYPE).unwrap(), "application/json");
+        assert_eq!(headers.get("x-amz-meta-project").unwrap(), "s3-simple");
+        assert_eq!(headers.get("x-amz-storage-class").unwrap(), "STANDARD_IA");
+        assert_eq!(headers.get("x-amz-tagging").unwrap(), "team=a%20b&env=prod");
+    }
+
+    #[test]
+    fn test_object_options_encryption() {
+        let headers = ObjectOptions::new()
+            .encryption(Encryption::S3)
+            .into_headers()
+            .unwrap();
+        assert_eq!(
+            headers.get("x-amz-server-side-encryption").unwrap(),
+            "AES256"
+        );
+        assert!(headers
+            .get("x-amz-server-side-encryption-aws-kms-key-id")
+            .is_none());
+
+        let headers = ObjectOptions::new()
+            .encryption(Encryption::Kms {
+                key_id: Some("key1".to_string()),
+            })
+            .into_headers()
+            .unwrap();
+        assert_eq!(
+            headers.get("x-amz-server-side-encryption").unwrap(),
+            "aws:kms"
+        );
+        assert_eq!(
+            headers
+                .get("x-amz-server-side-encryption-aws-kms-key-id")
+                .unwrap(),
+            "key1"
+        );
+    }
+
+    #[test]
+    fn test_sse_customer_key() {
+        let key = SseCustomerKey::new([7; 32]);
+        let mut headers = HeaderMap::new();
+        key.apply(&mut headers, false).unwrap();
+        key.apply(&mut headers, true).unwrap();
+
+        let md5 = headers
+            .get("x-amz-server-side-encryption-customer-key-md5")
+            .unwrap();
+        // base64 of the 16 byte digest
+        assert_eq!(md5.len(), 24);
+        assert_eq!(md5, md5_url_encode(&[7; 32]).as_str());
+        assert_eq!(
+            headers
+                .get("x-amz-server-side-encryption-customer-key")
+                .unwrap(),
+            general_purpose::STANDARD.encode([7; 32]).as_str()
+        );
+        assert_eq!(
+            headers
+                .get("x-amz-copy-source-server-side-encryption-customer-algorithm")
+                .unwrap(),
+            "AES256"
+        );
+        assert!(!format!("{:?}", key).contains(&general_purpose::STANDARD.encode([7; 32])));
+    }
+
+    #[test]
+    fn test_checksum_algorithm() {
+        // test vectors from the AWS docs for the content `Hello World`
+        let content = b"Hello World";
+        assert_eq!(
+            ChecksumAlgorithm::Sha256.checksum(content),
+            "pZGm1Av0IEBKARczz7exkNYsZb8LzaMrV7J32a2fFG4="
+        );
+        assert_eq!(ChecksumAlgorithm::Crc32.checksum(content), "ShexVg==");
+        assert_eq!(ChecksumAlgorithm::Crc32c.checksum(content), "aR2qLw==");
+    }
+
+    #[test]
+    fn test_object_options_invalid() {
+        assert!(ObjectOptions::new()
+            .cache_control("new\nline")
+            .into_headers()
+            .is_err());
+        assert!(matches!(
+            ObjectOptions::new()
+                .content_type("text/\nplain")
+                .into_headers(),
+            Err(S3Error::ContentType(_))
+        ));
+        assert!(ObjectOptions::new()
+            .metadata("no spaces", "value")
+            .into_headers()
+            .is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use crate::bucket::Bucket;
+use crate::error::S3Error;
+use crate::S3StatusCode;
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt};
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::io::{self, SeekFrom};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
+use tracing::debug;
+
+type ResponseFuture = Pin<Box<dyn Future<Output = Result<reqwest::Response, S3Error>> + Send>>;
+type BodyStream = Pin<Box<dyn Stream<Item = Result<Bytes, reqwest::Error>> + Send>>;
+
+enum State {
+    /// No request in flight, the next read starts a new one at the current position
+    Idle,
+    Requesting(ResponseFuture),
+    Reading(BodyStream),
+}
+
+/// Random access to a single object via ranged GETs, created with `Bucket::open_seekable()`.
+///
+/// Sequential reads are served from a single open response. Each read after a seek to another
+/// position opens a new ranged GET starting at the new position, so prefer large reads after a
+/// seek and avoid seeking back and forth in small steps.
+pub struct S3SeekableReader {
+    bucket: Bucket,
+    path: String,
+    len: u64,
+    pos: u64,
+    buf: Bytes,
+    state: State,
+}
+
+impl Debug for S3SeekableReader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("S3SeekableReader")
+            .field("path", &self.path)
+            .field("len", &self.len)
+            .field("pos", &self.pos)
+            .finish()
+    }
+}
+
+impl S3SeekableReader {
+    pub(crate) fn new(bucket: Bucket, path: String, len: u64) -> Self {
+        Self {
+            bucket,
+            path,
+            len,
+            pos: 0,
+            buf: Bytes::new(),
+            state: State::Idle,
+        }
+    }
+
+    /// The total size of the object
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The current position inside the object
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+}
+
+impl AsyncRead for S3SeekableReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        out: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        loop {
+            if this.pos >= this.len || out.remaining() == 0 {
+                return Poll::Ready(Ok(()));
+            }
+
+            if !this.buf.is_empty() {
+                let n = this.buf.len().min(out.remaining());
+                out.put_slice(&this.buf.split_to(n));
+                this.pos += n as u64;
+                return Poll::Ready(Ok(()));
+            }
+
+            match &mut this.state {
+                State::Idle => {
+                    debug!("ranged GET for {} from {}", this.path, this.pos);
+                    let bucket = this.bucket.clone();
+                    let path = this.path.clone();
+                    let start = this.pos;
+                    this.state = State::Requesting(Box::pin(async move {
+                        bucket.get_range(path, start, None).await
+                    }));
+                }
+
+                State::Requesting(fut) => {
+                    let res = match ready!(fut.as_mut().poll(cx)) {
+                        Ok(res) => res,
+                        Err(err) => {
+                            this.state = State::Idle;
+                            return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, err)));
+                        }
+                    };
+                    // a provider without range support returns the whole object instead
+                    if res.status() != S3StatusCode::PARTIAL_CONTENT && this.pos > 0 {
+                        this.state = State::Idle;
+                        return Poll::Ready(Err(io::Error::new(
+                            io::ErrorKind::Unsupported,
+                            "the object does not support range requests",
+                        )));
+                    }
+                    this.state = State::Reading(Box::pin(res.bytes_stream()));
+                }
+
+                State::Reading(stream) => match ready!(stream.poll_next_unpin(cx)) {
+                    Some(Ok(chunk)) => this.buf = chunk,
+                    Some(Err(err)) => {
+                        this.state = State::Idle;
+                        return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, err)));
+                    }
+                    None => {
+                        this.state = State::Idle;
+                        return Poll::Ready(Err(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            "the response ended before the end of the object",
+                        )));
+                    }
+                },
+            }
+        }
+    }
+}
+
+impl AsyncSeek for S3SeekableReader {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+        let this = self.get_mut();
+
+        let pos = match position {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::End(offset) => this.len.checked_add_signed(offset),
+            SeekFrom::Current(offset) => this.pos.checked_add_signed(offset),
+        };
+        let Some(pos) = pos else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            ));
+        };
+
+        // keep reading from the open response, if the position does not change
+        if pos != this.pos {
+            this.pos = pos;
+            this.buf = Bytes::new();
+            this.state = State::Idle;
+        }
+        Ok(())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Poll::Ready(Ok(self.pos))
+    }
+}
@@ -0,0 +1,352 @@
+use crate::error::S3Error;
+use crate::xml_escape;
+use bytes::{Buf, Bytes, BytesMut};
+use std::fmt;
+
+/// 4 byte total length + 4 byte headers length + 4 byte prelude CRC
+const PRELUDE_LEN: usize = 12;
+/// Prelude + 4 byte message CRC at the end
+const MIN_MESSAGE_LEN: usize = PRELUDE_LEN + 4;
+
+/// An SQL query for `Bucket::select_object_content()`
+#[derive(Debug, Clone)]
+pub struct SelectQuery {
+    /// The SQL expression like `SELECT * FROM S3Object s WHERE s.id > 10`
+    pub expression: String,
+    /// The format of the object which will be queried
+    pub input: SelectInput,
+    /// The format of the returned records
+    pub output: SelectOutput,
+}
+
+impl SelectQuery {
+    /// Query a CSV object with a header line, returning CSV records
+    pub fn csv<S: Into<String>>(expression: S) -> Self {
+        Self {
+            expression: expression.into(),
+            input: SelectInput::Csv {
+                use_header: true,
+                delimiter: ',',
+            },
+            output: SelectOutput::Csv { delimiter: ',' },
+        }
+    }
+
+    /// Query an object with line delimited JSON documents, returning JSON records
+    pub fn json<S: Into<String>>(expression: S) -> Self {
+        Self {
+            expression: expression.into(),
+            input: SelectInput::Json { lines: true },
+            output: SelectOutput::Json,
+        }
+    }
+
+    /// Query a Parquet object, returning JSON records
+    pub fn parquet<S: Into<String>>(expression: S) -> Self {
+        Self {
+            expression: expression.into(),
+            input: SelectInput::Parquet,
+            output: SelectOutput::Json,
+        }
+    }
+}
+
+impl fmt::Display for SelectQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<SelectObjectContentRequest xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\">\
+            <Expression>{}</Expression><ExpressionType>SQL</ExpressionType>\
+            <InputSerialization>{}</InputSerialization>\
+            <OutputSerialization>{}</OutputSerialization>\
+            </SelectObjectContentRequest>",
+            xml_escape(&self.expression),
+            self.input,
+            self.output,
+        )
+    }
+}
+
+/// The format of the object queried via S3 Select
+#[derive(Debug, Clone)]
+pub enum SelectInput {
+    Csv {
+        /// If `true`, the first line is used as header and columns can be referenced by name
+        use_header: bool,
+        delimiter: char,
+    },
+    Json {
+        /// `true` for line delimited documents, `false` for a single JSON document
+        lines: bool,
+    },
+    Parquet,
+}
+
+impl fmt::Display for SelectInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectInput::Csv {
+                use_header,
+                delimiter,
+            } => write!(
+                f,
+                "<CSV><FileHeaderInfo>{}</FileHeaderInfo><FieldDelimiter>{}</FieldDelimiter></CSV>",
+                if *use_header { "USE" } else { "NONE" },
+                xml_escape(&delimiter.to_string()),
+            ),
+            SelectInput::Json { lines } => write!(
+                f,
+                "<JSON><Type>{}</Type></JSON>",
+                if *lines { "LINES" } else { "DOCUMENT" }
+            ),
+            SelectInput::Parquet => write!(f, "<Parquet/>"),
+        }
+    }
+}
+
+/// The format of the records returned via S3 Select
+#[derive(Debug, Clone)]
+pub enum SelectOutput {
+    Csv { delimiter: char },
+    Json,
+}
+
+impl fmt::Display for SelectOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectOutput::Csv { delimiter } => write!(
+                f,
+                "<CSV><FieldDelimiter>{}</FieldDelimiter></CSV>",
+                xml_escape(&delimiter.to_string()),
+            ),
+            SelectOutput::Json => write!(f, "<JSON/>"),
+        }
+    }
+}
+
+/// A decoded event from an S3 Select response
+#[derive(Debug, PartialEq)]
+pub(crate) enum SelectEvent {
+    Records(Bytes),
+    End,
+    /// `Stats`, `Progress` and `Cont` events, which we do not care about
+    Other,
+}
+
+/// Incremental decoder for the `application/vnd.amazon.eventstream` framing
+#[derive(Debug, Default)]
+pub(crate) struct EventStreamDecoder {
+    buf: BytesMut,
+}
+
+impl EventStreamDecoder {
+    pub(crate) fn push(&mut self, data: &[u8]) {
+        self.buf.extend_from_slice(data);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Returns the next event, or `None` if more data is needed.
+    pub(crate) fn next_event(&mut self) -> Result<Option<SelectEvent>, S3Error> {
+        if self.buf.len() < PRELUDE_LEN {
+            return Ok(None);
+        }
+
+        let total_len = u32::from_be_bytes(self.buf[0..4].try_into().unwrap()) as usize;
+        let headers_len = u32::from_be_bytes(self.buf[4..8].try_into().unwrap()) as usize;
+        let prelude_crc = u32::from_be_bytes(self.buf[8..12].try_into().unwrap());
+        if crc32fast::hash(&self.buf[0..8]) != prelude_crc {
+            return Err(S3Error::EventStream(
+                "prelude checksum mismatch".to_string(),
+            ));
+        }
+        if total_len < MIN_MESSAGE_LEN || headers_len > total_len - MIN_MESSAGE_LEN {
+            return Err(S3Error::EventStream(format!(
+                "invalid message length {} with headers length {}",
+                total_len, headers_len
+            )));
+        }
+        if self.buf.len() < total_len {
+            return Ok(None);
+        }
+
+        let mut message = self.buf.split_to(total_len).freeze();
+        let message_crc = u32::from_be_bytes(message[total_len - 4..].try_into().unwrap());
+        if crc32fast::hash(&message[..total_len - 4]) != message_crc {
+            return Err(S3Error::EventStream(
+                "message checksum mismatch".to_string(),
+            ));
+        }
+
+        message.advance(PRELUDE_LEN);
+        let mut headers = message.split_to(headers_len);
+        let payload = message.split_to(message.len() - 4);
+
+        let mut message_type = None;
+        let mut event_type = None;
+        let mut error_code = None;
+        let mut error_message = None;
+        while headers.has_remaining() {
+            let (name, value) = decode_header(&mut headers)?;
+            match name.as_str() {
+                ":message-type" => message_type = Some(value),
+                ":event-type" => event_type = Some(value),
+                ":error-code" => error_code = Some(value),
+                ":error-message" => error_message = Some(value),
+                _ => {}
+            }
+        }
+
+        match message_type.as_deref() {
+            Some("event") => Ok(Some(match event_type.as_deref() {
+                Some("Records") => SelectEvent::Records(payload),
+                Some("End") => SelectEvent::End,
+                _ => SelectEvent::Other,
+            })),
+            Some("error") => Err(S3Error::EventStream(format!(
+                "{}: {}",
+                error_code.unwrap_or_default(),
+                error_message.unwrap_or_default()
+            ))),
+            typ => Err(S3Error::EventStream(format!(
+                "unexpected message type: {:?}",
+                typ
+            ))),
+        }
+    }
+}
+
+/// Decodes a single header. S3 Select only sends string values (type 7), everything else is
+/// rejected.
+fn decode_header(buf: &mut Bytes) -> Result<(String, String), S3Error> {
+    let err = || S3Error::EventStream("truncated header".to_string());
+
+    if buf.remaining() < 1 {
+        return Err(err());
+    }
+    let name_len = buf.get_u8() as usize;
+    if buf.remaining() < name_len + 1 {
+        return Err(err());
+    }
+    let name = String::from_utf8(buf.split_to(name_len).to_vec())?;
+
+    let value_type = buf.get_u8();
+    if value_type != 7 {
+        return Err(S3Error::EventStream(format!(
+            "unsupported header value type {} for '{}'",
+            value_type, name
+        )));
+    }
+    if buf.remaining() < 2 {
+        return Err(err());
+    }
+    let value_len = buf.get_u16() as usize;
+    if buf.remaining() < value_len {
+        return Err(err());
+    }
+    let value = String::from_utf8(buf.split_to(value_len).to_vec())?;
+
+    Ok((name, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn message(headers: &[(&str, &str)], payload: &[u8]) -> Vec<u8> {
+        let mut h = Vec::new();
+        for (name, value) in headers {
+            h.push(name.len() as u8);
+            h.extend_from_slice(name.as_bytes());
+            h.push(7);
+            h.extend_from_slice(&(value.len() as u16).to_be_bytes());
+            h.extend_from_slice(value.as_bytes());
+        }
+
+        let total_len = (MIN_MESSAGE_LEN + h.len() + payload.len()) as u32;
+        let mut msg = Vec::new();
+        msg.extend_from_slice(&total_len.to_be_bytes());
+        msg.extend_from_slice(&(h.len() as u32).to_be_bytes());
+        msg.extend_from_slice(&crc32fast::hash(&msg).to_be_bytes());
+        msg.extend_from_slice(&h);
+        msg.extend_from_slice(payload);
+        msg.extend_from_slice(&crc32fast::hash(&msg).to_be_bytes());
+        msg
+    }
+
+    #[test]
+    fn test_event_stream_decoder() {
+        let mut data = message(
+            &[(":message-type", "event"), (":event-type", "Records")],
+            b"a,b\n1,2\n",
+        );
+        data.extend(message(
+            &[(":message-type", "event"), (":event-type", "Stats")],
+            b"<Stats/>",
+        ));
+        data.extend(message(
+            &[(":message-type", "event"), (":event-type", "End")],
+            b"",
+        ));
+
+        // feed it byte by byte to make sure partial frames are handled
+        let mut decoder = EventStreamDecoder::default();
+        let mut events = Vec::new();
+        for b in data {
+            decoder.push(&[b]);
+            while let Some(event) = decoder.next_event().unwrap() {
+                events.push(event);
+            }
+        }
+
+        assert!(decoder.is_empty());
+        assert_eq!(
+            events,
+            vec![
+                SelectEvent::Records(Bytes::from_static(b"a,b\n1,2\n")),
+                SelectEvent::Other,
+                SelectEvent::End,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_event_stream_decoder_errors() {
+        let mut decoder = EventStreamDecoder::default();
+        decoder.push(&message(
+            &[
+                (":message-type", "error"),
+                (":error-code", "InvalidQuery"),
+                (":error-message", "bad sql"),
+            ],
+            b"",
+        ));
+        let err = decoder.next_event().unwrap_err();
+        assert_eq!(err.to_string(), "event stream: InvalidQuery: bad sql");
+
+        let mut data = message(&[(":message-type", "event")], b"payload");
+        let last = data.len() - 5;
+        data[last] ^= 0xff;
+        let mut decoder = EventStreamDecoder::default();
+        decoder.push(&data);
+        assert!(decoder.next_event().is_err());
+    }
+
+    #[test]
+    fn test_select_query_xml() {
+        let query = SelectQuery::csv("SELECT s.a FROM S3Object s WHERE s.b < 'x'");
+        assert_eq!(
+            query.to_string(),
+            "<SelectObjectContentRequest xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\">\
+            <Expression>SELECT s.a FROM S3Object s WHERE s.b &lt; &apos;x&apos;</Expression>\
+            <ExpressionType>SQL</ExpressionType>\
+            <InputSerialization><CSV><FileHeaderInfo>USE</FileHeaderInfo>\
+            <FieldDelimiter>,</FieldDelimiter></CSV></InputSerialization>\
+            <OutputSerialization><CSV><FieldDelimiter>,</FieldDelimiter></CSV>\
+            </OutputSerialization></SelectObjectContentRequest>"
+        );
+    }
+}
@@ -122,7 +122,7 @@ pub fn canonical_request(
     ))
 }
 
-fn scope_string(datetime: &OffsetDateTime, region: &Region) -> Result<String, S3Error> {
+pub fn scope_string(datetime: &OffsetDateTime, region: &Region) -> Result<String, S3Error> {
     Ok(format!(
         "{}/{}/s3/aws4_request",
         datetime.format(SHORT_DATE)?,
@@ -155,7 +155,10 @@ pub fn signing_key(
     secret.extend(b"AWS4");
     secret.extend(secret_key.as_ref().as_bytes());
 
-    let mut date_hmac = Hmac::<Sha256>::new_from_slice(secret.as_ref())?;
+    let date_hmac = Hmac::<Sha256>::new_from_slice(secret.as_ref());
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(secret.as_mut());
+    let mut date_hmac = date_hmac?;
     date_hmac.update(datetime.format(SHORT_DATE)?.as_bytes());
 
     let mut region_hmac = Hmac::<Sha256>::new_from_slice(&date_hmac.finalize().into_bytes())?;
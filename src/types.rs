@@ -1,5 +1,13 @@
+use crate::bucket::is_error_body;
+use crate::error::S3Error;
+use crate::xml_escape;
 use serde::Deserialize;
+use std::fmt;
 use std::str::FromStr;
+use std::time::Duration;
+use time::format_description::well_known::Rfc2822;
+use time::OffsetDateTime;
+use tracing::{error, warn};
 
 #[derive(Clone, Debug)]
 pub(crate) struct Multipart<'a> {
@@ -54,6 +62,13 @@ pub struct Object {
     #[serde(rename = "Size")]
     /// Size in bytes of the object.
     pub size: u64,
+    #[serde(rename = "ChecksumAlgorithm", default)]
+    /// The algorithms of the additional checksums like `CRC32C` or `SHA256`, if the object
+    /// was uploaded with any. S3 defines this as a list, even though there is usually only one.
+    pub checksum_algorithm: Vec<String>,
+    #[serde(rename = "ChecksumType")]
+    /// `FULL_OBJECT` or `COMPOSITE` for multipart uploads, if the object has a checksum
+    pub checksum_type: Option<String>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -72,6 +87,19 @@ pub struct HeadObjectResult {
     #[serde(rename = "CacheControl")]
     /// Specifies caching behavior along the request/reply chain.
     pub cache_control: Option<String>,
+    #[serde(rename = "ChecksumCRC32")]
+    /// The base64 encoded CRC32 checksum of the object. Only returned, if the object has been
+    /// stored with this checksum and `BucketOptions::checksum_mode` is set.
+    pub checksum_crc32: Option<String>,
+    #[serde(rename = "ChecksumCRC32C")]
+    /// The base64 encoded CRC32C checksum of the object, see `checksum_crc32`.
+    pub checksum_crc32c: Option<String>,
+    #[serde(rename = "ChecksumSHA1")]
+    /// The base64 encoded SHA1 checksum of the object, see `checksum_crc32`.
+    pub checksum_sha1: Option<String>,
+    #[serde(rename = "ChecksumSHA256")]
+    /// The base64 encoded SHA256 checksum of the object, see `checksum_crc32`.
+    pub checksum_sha256: Option<String>,
     #[serde(rename = "ContentDisposition")]
     /// Specifies presentational information for the object.
     pub content_disposition: Option<String>,
@@ -83,6 +111,10 @@ pub struct HeadObjectResult {
     pub content_language: Option<String>,
     #[serde(rename = "ContentLength")]
     /// Size of the body in bytes.
+    ///
+    /// Objects stored with `Content-Encoding: br` are decoded on the fly on GET. Since the stored
+    /// size does not match the decoded one, both this and `content_encoding` are `None` for
+    /// such objects.
     pub content_length: Option<u64>,
     #[serde(rename = "ContentType")]
     /// A standard MIME type describing the format of the object data.
@@ -171,11 +203,27 @@ impl GetAndConvertHeaders for http::header::HeaderMap {
     }
 }
 
+impl HeadObjectResult {
+    /// Returns `true` if the object can be requested in ranges via `Accept-Ranges: bytes`.
+    ///
+    /// Check this before relying on ranged requests, for instance for resumable downloads.
+    /// Without range support, a ranged GET may return the full object with a `200`.
+    pub fn supports_ranges(&self) -> bool {
+        self.accept_ranges
+            .as_deref()
+            .is_some_and(|v| v.trim().eq_ignore_ascii_case("bytes"))
+    }
+}
+
 impl From<&http::HeaderMap> for HeadObjectResult {
     fn from(headers: &http::HeaderMap) -> Self {
         let mut result = HeadObjectResult {
             accept_ranges: headers.get_string("accept-ranges"),
             cache_control: headers.get_string("Cache-Control"),
+            checksum_crc32: headers.get_string("x-amz-checksum-crc32"),
+            checksum_crc32c: headers.get_string("x-amz-checksum-crc32c"),
+            checksum_sha1: headers.get_string("x-amz-checksum-sha1"),
+            checksum_sha256: headers.get_string("x-amz-checksum-sha256"),
             content_disposition: headers.get_string("Content-Disposition"),
             content_encoding: headers.get_string("Content-Encoding"),
             content_language: headers.get_string("Content-Language"),
@@ -266,6 +314,12 @@ pub struct ListBucketResult {
     pub common_prefixes: Option<Vec<CommonPrefix>>,
 }
 
+#[derive(Deserialize, Debug)]
+pub(crate) struct CopyPartResult {
+    #[serde(rename = "ETag")]
+    pub e_tag: String,
+}
+
 #[derive(Deserialize, Debug)]
 pub(crate) struct InitiateMultipartUploadResponse {
     #[serde(rename = "Bucket")]
@@ -276,8 +330,826 @@ pub(crate) struct InitiateMultipartUploadResponse {
     pub upload_id: String,
 }
 
+#[derive(Deserialize, Debug)]
+pub(crate) struct ListMultipartUploadsResult {
+    #[serde(default, rename = "IsTruncated")]
+    pub is_truncated: bool,
+    #[serde(rename = "NextKeyMarker")]
+    pub next_key_marker: Option<String>,
+    #[serde(rename = "NextUploadIdMarker")]
+    pub next_upload_id_marker: Option<String>,
+    #[serde(rename = "Upload", default)]
+    pub uploads: Vec<MultipartUpload>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MultipartUpload {
+    #[serde(rename = "Key")]
+    /// The key of the object the upload was initiated for
+    pub key: String,
+    #[serde(rename = "UploadId")]
+    /// The ID of the multipart upload
+    pub upload_id: String,
+    #[serde(rename = "Initiated")]
+    /// Date and time the upload was initiated in RFC3339 format
+    pub initiated: String,
+    #[serde(rename = "StorageClass")]
+    /// The storage class of the object to be uploaded
+    pub storage_class: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ListVersionsResult {
+    pub is_truncated: bool,
+    pub next_key_marker: Option<String>,
+    pub next_version_id_marker: Option<String>,
+    /// Versions and delete markers in the order returned by S3
+    pub versions: Vec<VersionEntry>,
+}
+
+/// `Version` and `DeleteMarker` elements are interleaved, so they are parsed as a sequence
+/// of all children to keep their order.
+#[derive(Deserialize, Debug)]
+struct ListVersionsResultXml {
+    #[serde(rename = "$value", default)]
+    children: Vec<ListVersionsChild>,
+}
+
+#[derive(Deserialize, Debug)]
+enum ListVersionsChild {
+    IsTruncated(bool),
+    NextKeyMarker(String),
+    NextVersionIdMarker(String),
+    Version(VersionXml),
+    DeleteMarker(VersionXml),
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize, Debug)]
+struct VersionXml {
+    #[serde(rename = "Key")]
+    key: String,
+    #[serde(rename = "VersionId")]
+    version_id: String,
+    #[serde(rename = "IsLatest", default)]
+    is_latest: bool,
+    #[serde(rename = "LastModified")]
+    last_modified: String,
+    #[serde(rename = "ETag")]
+    e_tag: Option<String>,
+    #[serde(rename = "Size")]
+    size: Option<u64>,
+}
+
+impl<'de> Deserialize<'de> for ListVersionsResult {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let xml = ListVersionsResultXml::deserialize(deserializer)?;
+        let mut result = ListVersionsResult::default();
+
+        for child in xml.children {
+            match child {
+                ListVersionsChild::IsTruncated(truncated) => result.is_truncated = truncated,
+                ListVersionsChild::NextKeyMarker(marker) => result.next_key_marker = Some(marker),
+                ListVersionsChild::NextVersionIdMarker(marker) => {
+                    result.next_version_id_marker = Some(marker)
+                }
+                ListVersionsChild::Version(v) => result.versions.push(v.into_entry(false)),
+                ListVersionsChild::DeleteMarker(v) => result.versions.push(v.into_entry(true)),
+                ListVersionsChild::Other => {}
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl VersionXml {
+    fn into_entry(self, is_delete_marker: bool) -> VersionEntry {
+        VersionEntry {
+            key: self.key,
+            version_id: self.version_id,
+            is_latest: self.is_latest,
+            is_delete_marker,
+            last_modified: self.last_modified,
+            e_tag: self.e_tag,
+            size: self.size,
+        }
+    }
+}
+
+/// A single object version or delete marker in a versioned bucket
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionEntry {
+    pub key: String,
+    pub version_id: String,
+    /// `true` for the current version of the key
+    pub is_latest: bool,
+    /// `true` if this version is a delete marker, which has no content
+    pub is_delete_marker: bool,
+    /// Date and time the version was created in RFC3339 format
+    pub last_modified: String,
+    /// Not set for delete markers
+    pub e_tag: Option<String>,
+    /// Not set for delete markers
+    pub size: Option<u64>,
+}
+
+/// The result of `Bucket::delete_many()`
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct DeleteResult {
+    #[serde(rename = "Deleted", default)]
+    /// All successfully deleted objects
+    pub deleted: Vec<DeletedObject>,
+    #[serde(rename = "Error", default)]
+    /// All objects which could not be deleted
+    pub errors: Vec<DeleteError>,
+}
+
+/// The result of `Bucket::delete_prefix()`
+#[derive(Debug, Clone, Default)]
+pub struct DeleteSummary {
+    /// The amount of deleted objects
+    pub count: usize,
+    /// The summed up size of all deleted objects, as reported by the listing
+    pub bytes_freed: u64,
+    /// All objects which could not be deleted
+    pub errors: Vec<DeleteError>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct DeletedObject {
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "VersionId")]
+    /// The version ID of the deleted object in a versioned bucket
+    pub version_id: Option<String>,
+    #[serde(rename = "DeleteMarker")]
+    /// If a delete marker has been created in a versioned bucket
+    pub delete_marker: Option<bool>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct DeleteError {
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "Code")]
+    /// The S3 error code like `AccessDenied`
+    pub code: String,
+    #[serde(rename = "Message", default)]
+    pub message: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct LegalHold {
+    #[serde(rename = "Status")]
+    pub status: String,
+}
+
+/// The S3 storage classes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageClass {
+    Standard,
+    ReducedRedundancy,
+    StandardIa,
+    OnezoneIa,
+    IntelligentTiering,
+    Glacier,
+    GlacierIr,
+    DeepArchive,
+}
+
+impl StorageClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StorageClass::Standard => "STANDARD",
+            StorageClass::ReducedRedundancy => "REDUCED_REDUNDANCY",
+            StorageClass::StandardIa => "STANDARD_IA",
+            StorageClass::OnezoneIa => "ONEZONE_IA",
+            StorageClass::IntelligentTiering => "INTELLIGENT_TIERING",
+            StorageClass::Glacier => "GLACIER",
+            StorageClass::GlacierIr => "GLACIER_IR",
+            StorageClass::DeepArchive => "DEEP_ARCHIVE",
+        }
+    }
+}
+
+impl From<StorageClass> for String {
+    fn from(class: StorageClass) -> Self {
+        class.as_str().to_string()
+    }
+}
+
+/// The Object Lock retention mode
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetentionMode {
+    /// Can be shortened or removed by users with the `s3:BypassGovernanceRetention` permission
+    #[serde(rename = "GOVERNANCE")]
+    Governance,
+    /// Cannot be shortened or removed by anyone until it expires
+    #[serde(rename = "COMPLIANCE")]
+    Compliance,
+}
+
+impl RetentionMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RetentionMode::Governance => "GOVERNANCE",
+            RetentionMode::Compliance => "COMPLIANCE",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub(crate) struct LocationConstraint {
+    #[serde(rename = "$text", default)]
+    pub region: String,
+}
+
+impl LocationConstraint {
+    /// S3 returns an empty constraint for `us-east-1` and the legacy `EU` for `eu-west-1`.
+    pub fn region(&self) -> &str {
+        match self.region.trim() {
+            "" => "us-east-1",
+            "EU" => "eu-west-1",
+            region => region,
+        }
+    }
+}
+
+/// The Object Lock retention of a single object
+#[derive(Deserialize, Debug, Clone)]
+pub struct ObjectRetention {
+    #[serde(rename = "Mode")]
+    pub mode: RetentionMode,
+    #[serde(rename = "RetainUntilDate")]
+    /// Date and time until the object is retained in RFC3339 format
+    pub retain_until_date: String,
+}
+
+/// A single CORS rule of a bucket
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct CorsRule {
+    #[serde(rename = "AllowedOrigin", default)]
+    /// Origins like `https://example.com` or `*`
+    pub allowed_origins: Vec<String>,
+    #[serde(rename = "AllowedMethod", default)]
+    /// `GET`, `PUT`, `POST`, `DELETE` or `HEAD`
+    pub allowed_methods: Vec<String>,
+    #[serde(rename = "AllowedHeader", default)]
+    /// Headers allowed in a preflight request via `Access-Control-Request-Headers`
+    pub allowed_headers: Vec<String>,
+    #[serde(rename = "ExposeHeader", default)]
+    /// Response headers the browser may access, like `ETag`
+    pub expose_headers: Vec<String>,
+    #[serde(rename = "MaxAgeSeconds")]
+    /// How long the browser may cache the preflight response
+    pub max_age_seconds: Option<u32>,
+}
+
+impl fmt::Display for CorsRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<CORSRule>")?;
+        for origin in &self.allowed_origins {
+            write!(f, "<AllowedOrigin>{}</AllowedOrigin>", xml_escape(origin))?;
+        }
+        for method in &self.allowed_methods {
+            write!(f, "<AllowedMethod>{}</AllowedMethod>", xml_escape(method))?;
+        }
+        for header in &self.allowed_headers {
+            write!(f, "<AllowedHeader>{}</AllowedHeader>", xml_escape(header))?;
+        }
+        for header in &self.expose_headers {
+            write!(f, "<ExposeHeader>{}</ExposeHeader>", xml_escape(header))?;
+        }
+        if let Some(max_age) = self.max_age_seconds {
+            write!(f, "<MaxAgeSeconds>{}</MaxAgeSeconds>", max_age)?;
+        }
+        write!(f, "</CORSRule>")
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct CorsConfiguration {
+    #[serde(rename = "CORSRule", default)]
+    pub rules: Vec<CorsRule>,
+}
+
+impl fmt::Display for CorsConfiguration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<CORSConfiguration xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\">"
+        )?;
+        for rule in &self.rules {
+            write!(f, "{}", rule)?;
+        }
+        write!(f, "</CORSConfiguration>")
+    }
+}
+
+/// Who owns newly uploaded objects and whether ACLs are in effect
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectOwnership {
+    /// ACLs are disabled and the bucket owner owns all objects
+    BucketOwnerEnforced,
+    /// The bucket owner owns objects uploaded with the `bucket-owner-full-control` ACL
+    BucketOwnerPreferred,
+    /// The uploading account owns the object
+    ObjectWriter,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct OwnershipControls {
+    #[serde(rename = "Rule", default)]
+    pub rules: Vec<OwnershipControlsRule>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct OwnershipControlsRule {
+    #[serde(rename = "ObjectOwnership")]
+    pub object_ownership: ObjectOwnership,
+}
+
+/// The public access block configuration of a bucket. Missing flags are `false`.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PublicAccessBlock {
+    #[serde(rename = "BlockPublicAcls", default)]
+    /// Rejects requests which set public ACLs
+    pub block_public_acls: bool,
+    #[serde(rename = "IgnorePublicAcls", default)]
+    /// Ignores all public ACLs on the bucket and its objects
+    pub ignore_public_acls: bool,
+    #[serde(rename = "BlockPublicPolicy", default)]
+    /// Rejects bucket policies which grant public access
+    pub block_public_policy: bool,
+    #[serde(rename = "RestrictPublicBuckets", default)]
+    /// Restricts access to buckets with a public policy to AWS services and the bucket owner
+    pub restrict_public_buckets: bool,
+}
+
 #[derive(Debug)]
+#[non_exhaustive]
 pub struct PutStreamResponse {
     pub status_code: u16,
     pub uploaded_bytes: usize,
+    /// The version of the new object in a versioned bucket
+    pub version_id: Option<String>,
+    /// The ETag of the new object. For multipart uploads, this is not an MD5 of the content,
+    /// but has the form `"<hash>-<number of parts>"`.
+    pub etag: Option<String>,
+}
+
+impl PutStreamResponse {
+    /// From the response of a single PUT, which returns the ETag as a header
+    pub(crate) fn from_put(res: &reqwest::Response, uploaded_bytes: usize) -> Self {
+        let op = OpResponse::from(res);
+        Self {
+            status_code: op.status.as_u16(),
+            uploaded_bytes,
+            version_id: op.version_id().map(String::from),
+            etag: op.e_tag().map(String::from),
+        }
+    }
+
+    /// From the response of a `CompleteMultipartUpload`, which returns the ETag in the body
+    pub(crate) async fn from_complete(
+        res: reqwest::Response,
+        uploaded_bytes: usize,
+    ) -> Result<Self, S3Error> {
+        let op = OpResponse::from(&res);
+        let body = res.bytes().await?;
+        // S3 may answer a failed completion with a `200` and an `<Error>` document
+        if is_error_body(&body) {
+            let body = String::from_utf8_lossy(&body).to_string();
+            error!(
+                "CompleteMultipartUpload failed with HTTP {}: {}",
+                op.status, body
+            );
+            return Err(S3Error::HttpFailWithBody(op.status.as_u16(), body));
+        }
+
+        let etag =
+            match quick_xml::de::from_reader::<_, CompleteMultipartUploadResult>(body.as_ref()) {
+                Ok(result) => result.e_tag,
+                Err(err) => {
+                    // the upload itself has succeeded at this point
+                    warn!("cannot parse the CompleteMultipartUpload result: {}", err);
+                    None
+                }
+            };
+
+        Ok(Self {
+            status_code: op.status.as_u16(),
+            uploaded_bytes,
+            version_id: op.version_id().map(String::from),
+            etag,
+        })
+    }
+}
+
+/// The parsed response of a single PUT
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PutResult {
+    /// The HTTP status code of the PUT, the same as `PutStreamResponse::status_code`
+    pub status_code: u16,
+    /// The ETag of the new object
+    pub etag: Option<String>,
+    /// The version of the new object in a versioned bucket
+    pub version_id: Option<String>,
+}
+
+impl From<&reqwest::Response> for PutResult {
+    fn from(res: &reqwest::Response) -> Self {
+        let op = OpResponse::from(res);
+        Self {
+            status_code: op.status.as_u16(),
+            etag: op.e_tag().map(String::from),
+            version_id: op.version_id().map(String::from),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct CompleteMultipartUploadResult {
+    #[serde(rename = "ETag")]
+    e_tag: Option<String>,
+}
+
+/// The status and headers of a successful operation, for a uniform access to values like
+/// the version ID across all operations that do not return the raw `S3Response` anyway.
+///
+/// Can be created from any `S3Response` as well.
+#[derive(Debug, Clone)]
+pub struct OpResponse {
+    pub status: http::StatusCode,
+    pub headers: http::HeaderMap,
+}
+
+impl OpResponse {
+    /// `x-amz-version-id` - the version of the object in a versioned bucket
+    pub fn version_id(&self) -> Option<&str> {
+        self.header("x-amz-version-id")
+    }
+
+    /// `x-amz-request-id` - useful when contacting the support of your provider
+    pub fn request_id(&self) -> Option<&str> {
+        self.header("x-amz-request-id")
+    }
+
+    /// `ETag` of the object, if the operation returned one
+    pub fn e_tag(&self) -> Option<&str> {
+        self.header("etag")
+    }
+
+    /// `Content-Disposition` of the object, which can be forwarded as-is by download proxies
+    pub fn content_disposition(&self) -> Option<&str> {
+        self.header("content-disposition")
+    }
+
+    /// The decoded filename from the `Content-Disposition`, if there is one
+    pub fn filename(&self) -> Option<String> {
+        crate::parse_disposition_filename(self.content_disposition()?)
+    }
+
+    /// The `max-age` directive of the `Cache-Control` header
+    pub fn cache_max_age(&self) -> Option<Duration> {
+        self.header("cache-control")?
+            .split(',')
+            .filter_map(|directive| directive.split_once('='))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("max-age"))
+            .and_then(|(_, value)| value.trim().trim_matches('"').parse::<u64>().ok())
+            .map(Duration::from_secs)
+    }
+
+    /// The `Expires` header as a typed value, `None` if it is missing or not a valid
+    /// HTTP date like `Wed, 21 Oct 2015 07:28:00 GMT`
+    pub fn expires_time(&self) -> Option<OffsetDateTime> {
+        OffsetDateTime::parse(self.header("expires")?.trim(), &Rfc2822).ok()
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name)?.to_str().ok()
+    }
+}
+
+impl From<&reqwest::Response> for OpResponse {
+    fn from(res: &reqwest::Response) -> Self {
+        Self {
+            status: res.status(),
+            headers: res.headers().clone(),
+        }
+    }
+}
+
+/// A presigned POST policy for browser based uploads via an HTML form
+#[derive(Debug, Clone)]
+pub struct PresignedPost {
+    /// The URL the form must be sent to
+    pub url: String,
+    /// All fields that must be part of the form, in this order, before the `file` field.
+    /// The `key` may be changed, as long as it matches the policy conditions.
+    pub fields: Vec<(String, String)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn test_put_stream_response_from_complete() {
+        let complete = |body: &'static str| {
+            let res = http::Response::builder()
+                .status(200)
+                .header("x-amz-version-id", "v1")
+                .body(body)
+                .unwrap();
+            reqwest::Response::from(res)
+        };
+
+        let res = PutStreamResponse::from_complete(
+            complete(
+                "<CompleteMultipartUploadResult><ETag>&quot;etag-2&quot;</ETag>\
+                </CompleteMultipartUploadResult>",
+            ),
+            13,
+        )
+        .await
+        .unwrap();
+        assert_eq!(res.status_code, 200);
+        assert_eq!(res.uploaded_bytes, 13);
+        assert_eq!(res.version_id.as_deref(), Some("v1"));
+        assert_eq!(res.etag.as_deref(), Some("\"etag-2\""));
+
+        // a failed completion with a success status
+        let err = PutStreamResponse::from_complete(
+            complete("<Error><Code>InternalError</Code></Error>"),
+            13,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, S3Error::HttpFailWithBody(200, _)));
+
+        // the upload has succeeded, only the ETag is missing
+        let res = PutStreamResponse::from_complete(complete("garbage"), 13)
+            .await
+            .unwrap();
+        assert_eq!(res.etag, None);
+    }
+
+    #[test]
+    fn test_list_bucket_result_checksums() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Name>examplebucket</Name>
+  <IsTruncated>false</IsTruncated>
+  <Contents>
+    <Key>with-checksum.txt</Key>
+    <LastModified>2024-01-01T00:00:00.000Z</LastModified>
+    <ETag>"599393a2c526c680119d84155d90f1e5"</ETag>
+    <ChecksumAlgorithm>CRC32C</ChecksumAlgorithm>
+    <ChecksumType>FULL_OBJECT</ChecksumType>
+    <Size>8</Size>
+    <StorageClass>STANDARD</StorageClass>
+  </Contents>
+  <Contents>
+    <Key>legacy.txt</Key>
+    <LastModified>2024-01-01T00:00:00.000Z</LastModified>
+    <Size>8</Size>
+  </Contents>
+</ListBucketResult>"#;
+
+        let res: ListBucketResult = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(res.contents[0].checksum_algorithm, vec!["CRC32C"]);
+        assert_eq!(
+            res.contents[0].checksum_type.as_deref(),
+            Some("FULL_OBJECT")
+        );
+        assert!(res.contents[1].checksum_algorithm.is_empty());
+        assert_eq!(res.contents[1].checksum_type, None);
+    }
+
+    #[test]
+    fn test_location_constraint() {
+        for (xml, expected) in [
+            (
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<LocationConstraint xmlns="http://s3.amazonaws.com/doc/2006-03-01/">eu-central-1</LocationConstraint>"#,
+                "eu-central-1",
+            ),
+            (
+                r#"<LocationConstraint xmlns="http://s3.amazonaws.com/doc/2006-03-01/"/>"#,
+                "us-east-1",
+            ),
+            ("<LocationConstraint>EU</LocationConstraint>", "eu-west-1"),
+        ] {
+            let location: LocationConstraint = quick_xml::de::from_str(xml).unwrap();
+            assert_eq!(location.region(), expected);
+        }
+    }
+
+    #[test]
+    fn test_list_versions_result() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListVersionsResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Name>bucket</Name>
+  <Prefix>my-image.jpg</Prefix>
+  <KeyMarker></KeyMarker>
+  <VersionIdMarker></VersionIdMarker>
+  <MaxKeys>2</MaxKeys>
+  <IsTruncated>true</IsTruncated>
+  <NextKeyMarker>my-image.jpg</NextKeyMarker>
+  <NextVersionIdMarker>v2</NextVersionIdMarker>
+  <DeleteMarker>
+    <Key>my-image.jpg</Key>
+    <VersionId>v3</VersionId>
+    <IsLatest>true</IsLatest>
+    <LastModified>2024-01-03T00:00:00.000Z</LastModified>
+  </DeleteMarker>
+  <Version>
+    <Key>my-image.jpg</Key>
+    <VersionId>v2</VersionId>
+    <IsLatest>false</IsLatest>
+    <LastModified>2024-01-02T00:00:00.000Z</LastModified>
+    <ETag>"fba9dede5f27731c9771645a39863328"</ETag>
+    <Size>434234</Size>
+    <StorageClass>STANDARD</StorageClass>
+  </Version>
+</ListVersionsResult>"#;
+
+        let res: ListVersionsResult = quick_xml::de::from_str(xml).unwrap();
+        assert!(res.is_truncated);
+        assert_eq!(res.next_key_marker.as_deref(), Some("my-image.jpg"));
+        assert_eq!(res.next_version_id_marker.as_deref(), Some("v2"));
+        assert_eq!(res.versions.len(), 2);
+        assert!(res.versions[0].is_delete_marker);
+        assert!(res.versions[0].is_latest);
+        assert_eq!(res.versions[0].size, None);
+        assert!(!res.versions[1].is_delete_marker);
+        assert_eq!(res.versions[1].version_id, "v2");
+        assert_eq!(res.versions[1].size, Some(434234));
+    }
+
+    #[test]
+    fn test_ownership_controls() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<OwnershipControls xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Rule>
+    <ObjectOwnership>BucketOwnerEnforced</ObjectOwnership>
+  </Rule>
+</OwnershipControls>"#;
+        let controls: OwnershipControls = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(
+            controls.rules[0].object_ownership,
+            ObjectOwnership::BucketOwnerEnforced
+        );
+    }
+
+    #[test]
+    fn test_public_access_block() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<PublicAccessBlockConfiguration xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <BlockPublicAcls>true</BlockPublicAcls>
+  <IgnorePublicAcls>false</IgnorePublicAcls>
+  <BlockPublicPolicy>true</BlockPublicPolicy>
+</PublicAccessBlockConfiguration>"#;
+        let block: PublicAccessBlock = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(
+            block,
+            PublicAccessBlock {
+                block_public_acls: true,
+                ignore_public_acls: false,
+                block_public_policy: true,
+                restrict_public_buckets: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_list_multipart_uploads_result() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListMultipartUploadsResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Bucket>bucket</Bucket>
+  <KeyMarker></KeyMarker>
+  <UploadIdMarker></UploadIdMarker>
+  <NextKeyMarker>my-movie.m2ts</NextKeyMarker>
+  <NextUploadIdMarker>YW55IGlkZWEgd2h5IGVsdmluZydzIHVwbG9hZCBmYWlsZWQ</NextUploadIdMarker>
+  <MaxUploads>3</MaxUploads>
+  <IsTruncated>true</IsTruncated>
+  <Upload>
+    <Key>my-divisor</Key>
+    <UploadId>XMgbGlrZSBlbHZpbmcncyBub3QgaGF2aW5nIG11Y2ggbHVjaw</UploadId>
+    <StorageClass>REDUCED_REDUNDANCY</StorageClass>
+    <Initiated>2010-11-10T20:48:33.000Z</Initiated>
+  </Upload>
+  <Upload>
+    <Key>my-movie.m2ts</Key>
+    <UploadId>VXBsb2FkIElEIGZvciBlbHZpbmcncyBteS1tb3ZpZS5tMnRzIHVwbG9hZA</UploadId>
+    <StorageClass>STANDARD</StorageClass>
+    <Initiated>2010-11-10T20:48:33.000Z</Initiated>
+  </Upload>
+</ListMultipartUploadsResult>"#;
+
+        let res: ListMultipartUploadsResult = quick_xml::de::from_str(xml).unwrap();
+        assert!(res.is_truncated);
+        assert_eq!(res.next_key_marker.as_deref(), Some("my-movie.m2ts"));
+        assert_eq!(res.uploads.len(), 2);
+        assert_eq!(res.uploads[0].key, "my-divisor");
+        assert_eq!(res.uploads[1].initiated, "2010-11-10T20:48:33.000Z");
+    }
+
+    #[test]
+    fn test_op_response() {
+        let res = http::Response::builder()
+            .status(200)
+            .header("x-amz-version-id", "3HL4kqtJlcpXroDTDmJ+rmSpXd3dIbrHY")
+            .header("x-amz-request-id", "656c76696e6727732072657175657374")
+            .body("")
+            .unwrap();
+        let res = OpResponse::from(&reqwest::Response::from(res));
+
+        assert_eq!(res.status, http::StatusCode::OK);
+        assert_eq!(res.version_id(), Some("3HL4kqtJlcpXroDTDmJ+rmSpXd3dIbrHY"));
+        assert_eq!(res.request_id(), Some("656c76696e6727732072657175657374"));
+        assert_eq!(res.e_tag(), None);
+    }
+
+    #[test]
+    fn test_op_response_caching() {
+        let res = http::Response::builder()
+            .header("cache-control", "public, Max-Age=\"3600\", must-revalidate")
+            .header("expires", "Wed, 21 Oct 2015 07:28:00 GMT")
+            .body("")
+            .unwrap();
+        let res = OpResponse::from(&reqwest::Response::from(res));
+        assert_eq!(res.cache_max_age(), Some(Duration::from_secs(3600)));
+        assert_eq!(res.expires_time().unwrap().unix_timestamp(), 1_445_412_480);
+
+        let res = http::Response::builder()
+            .header("cache-control", "no-cache")
+            .header("expires", "0")
+            .body("")
+            .unwrap();
+        let res = OpResponse::from(&reqwest::Response::from(res));
+        assert_eq!(res.cache_max_age(), None);
+        assert_eq!(res.expires_time(), None);
+    }
+
+    #[test]
+    fn test_cors_configuration() {
+        let config = CorsConfiguration {
+            rules: vec![CorsRule {
+                allowed_origins: vec!["https://example.com".to_string()],
+                allowed_methods: vec!["GET".to_string(), "PUT".to_string()],
+                allowed_headers: vec!["*".to_string()],
+                expose_headers: vec!["ETag".to_string()],
+                max_age_seconds: Some(3000),
+            }],
+        };
+        let xml = config.to_string();
+        assert_eq!(
+            xml,
+            "<CORSConfiguration xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\"><CORSRule>\
+            <AllowedOrigin>https://example.com</AllowedOrigin>\
+            <AllowedMethod>GET</AllowedMethod><AllowedMethod>PUT</AllowedMethod>\
+            <AllowedHeader>*</AllowedHeader><ExposeHeader>ETag</ExposeHeader>\
+            <MaxAgeSeconds>3000</MaxAgeSeconds></CORSRule></CORSConfiguration>"
+        );
+
+        // must round trip
+        let parsed: CorsConfiguration = quick_xml::de::from_str(&xml).unwrap();
+        assert_eq!(parsed.rules, config.rules);
+    }
+
+    #[test]
+    fn test_supports_ranges() {
+        let mut headers = http::HeaderMap::new();
+        assert!(!HeadObjectResult::from(&headers).supports_ranges());
+
+        headers.insert("accept-ranges", "none".parse().unwrap());
+        assert!(!HeadObjectResult::from(&headers).supports_ranges());
+
+        headers.insert("accept-ranges", "bytes".parse().unwrap());
+        assert!(HeadObjectResult::from(&headers).supports_ranges());
+    }
+
+    #[test]
+    fn test_storage_class() {
+        assert_eq!(String::from(StorageClass::StandardIa), "STANDARD_IA");
+        assert_eq!(StorageClass::GlacierIr.as_str(), "GLACIER_IR");
+    }
+
+    #[test]
+    fn test_object_retention() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Retention xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Mode>COMPLIANCE</Mode>
+  <RetainUntilDate>2030-01-01T00:00:00.000Z</RetainUntilDate>
+</Retention>"#;
+
+        let res: ObjectRetention = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(res.mode, RetentionMode::Compliance);
+        assert_eq!(res.retain_until_date, "2030-01-01T00:00:00.000Z");
+    }
 }